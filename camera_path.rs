@@ -0,0 +1,282 @@
+use crate::three;
+use std::*;
+use time::Instant;
+
+// Time taken to fly from one keyframe to the next, in seconds, unless a path file says otherwise.
+const DEFAULT_SECONDS_PER_KEYFRAME: f32 = 2.;
+
+// A view of the model captured for a camera path: the camera's angles, its distance from the point it orbits, and that point.
+#[derive(Copy, Clone)]
+pub struct Keyframe {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    pub distance: f32,
+    pub target: three::Point,
+}
+
+// A fly-through made of keyframes, played back by smoothly interpolating between them at an even pace.
+pub struct CameraPath {
+    // Views to pass through, in order.
+    pub keyframes: Vec<Keyframe>,
+    // Time taken from each keyframe to the next, in seconds.
+    pub seconds_per_keyframe: f32,
+    // When playback started, or None while stopped.
+    playing_since: Option<Instant>,
+}
+
+impl CameraPath {
+    // Creates an empty camera path.
+    pub fn new() -> CameraPath {
+        CameraPath { keyframes: Vec::new(), seconds_per_keyframe: DEFAULT_SECONDS_PER_KEYFRAME, playing_since: None }
+    }
+
+    // Loads a camera path from a JSON file written by save.
+    pub fn load(path: &str) -> Result<CameraPath, Box<dyn error::Error>> {
+        let text = fs::read_to_string(path)?;
+        let invalid = || format!("Invalid camera path \"{}\".", path);
+        let json = JsonParser::parse(&text).ok_or_else(invalid)?;
+
+        let mut camera_path = CameraPath::new();
+        if let Some(seconds) = json.get("seconds_per_keyframe") {
+            camera_path.seconds_per_keyframe = seconds.number().filter(|seconds| seconds.is_finite() && *seconds > 0.).ok_or_else(invalid)?;
+        }
+        let keyframes = match json.get("keyframes") {
+            Some(Json::Array(keyframes)) => keyframes,
+            _ => return Err(Box::from(invalid())),
+        };
+        for keyframe in keyframes {
+            let number = |key: &str| keyframe.get(key).and_then(Json::number).filter(|value| value.is_finite()).ok_or_else(invalid);
+            let target = match keyframe.get("target") {
+                Some(Json::Array(components)) if components.len() == 3 => {
+                    let components: Option<Vec<f32>> = components.iter().map(Json::number).collect();
+                    components.ok_or_else(invalid)?
+                }
+                _ => return Err(Box::from(invalid())),
+            };
+            camera_path.keyframes.push(Keyframe {
+                yaw: number("yaw")?,
+                pitch: number("pitch")?,
+                roll: number("roll")?,
+                distance: number("distance")?,
+                target: three::Point::new(target[0], target[1], target[2]),
+            });
+        }
+        Ok(camera_path)
+    }
+
+    // Writes the camera path to a JSON file, with one keyframe per line.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let keyframes: Vec<String> = self.keyframes
+            .iter()
+            .map(|keyframe| format!(
+                "    {{\"yaw\": {}, \"pitch\": {}, \"roll\": {}, \"distance\": {}, \"target\": [{}, {}, {}]}}",
+                keyframe.yaw, keyframe.pitch, keyframe.roll, keyframe.distance, keyframe.target.x, keyframe.target.y, keyframe.target.z,
+            ))
+            .collect();
+        fs::write(path, format!(
+            "{{\n  \"seconds_per_keyframe\": {},\n  \"keyframes\": [\n{}\n  ]\n}}\n",
+            self.seconds_per_keyframe,
+            keyframes.join(",\n"),
+        ))
+    }
+
+    // Returns how long playing back the whole path takes, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.len().saturating_sub(1) as f32 * self.seconds_per_keyframe
+    }
+
+    // Returns the view a number of seconds into the path, or None if it has no keyframes.
+    // Each parameter follows a Catmull-Rom spline through the keyframes, so the camera moves smoothly through them instead of
+    // turning sharply at each one. Yaw and roll take the shorter way around between keyframes.
+    pub fn at(&self, seconds: f32) -> Option<Keyframe> {
+        let last = self.keyframes.len().checked_sub(1)?;
+        let position = (seconds / self.seconds_per_keyframe).clamp(0., last as f32);
+        let segment = (position.floor() as usize).min(last.saturating_sub(1));
+        let fraction = position - segment as f32;
+
+        // Unwrap the angles of the four keyframes around the segment so that none of them is more than half a turn from the one before.
+        let indices = [segment.saturating_sub(1), segment, (segment + 1).min(last), (segment + 2).min(last)];
+        let frames = indices.map(|index| self.keyframes[index]);
+        let unwrapped = |angle: fn(&Keyframe) -> f32| {
+            let mut angles = frames.map(|frame| angle(&frame));
+            for index in 1..4 {
+                let turn = (angles[index] - angles[index - 1] + f32::consts::PI).rem_euclid(f32::consts::TAU) - f32::consts::PI;
+                angles[index] = angles[index - 1] + turn;
+            }
+            angles
+        };
+
+        let spline = |values: [f32; 4]| catmull_rom(values, fraction);
+        let distance = spline(frames.map(|frame| frame.distance)).max(frames[1].distance.min(frames[2].distance) * 0.5);
+        Some(Keyframe {
+            yaw: spline(unwrapped(|frame| frame.yaw)),
+            pitch: spline(frames.map(|frame| frame.pitch)),
+            roll: spline(unwrapped(|frame| frame.roll)),
+            distance,
+            target: three::Point::new(
+                spline(frames.map(|frame| frame.target.x)),
+                spline(frames.map(|frame| frame.target.y)),
+                spline(frames.map(|frame| frame.target.z)),
+            ),
+        })
+    }
+
+    // Starts playing the path from its first keyframe, or stops it if it is already playing.
+    pub fn toggle_playback(&mut self) {
+        self.playing_since = match self.playing_since {
+            None if !self.keyframes.is_empty() => Some(Instant::now()),
+            _ => None,
+        };
+    }
+
+    // Returns whether the path is being played back.
+    pub fn playing(&self) -> bool {
+        self.playing_since.is_some()
+    }
+
+    // Returns the view to show while playing, stopping once the end of the path has been shown.
+    pub fn poll(&mut self) -> Option<Keyframe> {
+        let seconds = self.playing_since?.elapsed().as_secs_f32();
+        if seconds >= self.duration() {
+            self.playing_since = None;
+        }
+        self.at(seconds)
+    }
+}
+
+// Interpolates between the middle two of four evenly spaced values with a Catmull-Rom spline, at a fraction of the way from the second to the third.
+fn catmull_rom(values: [f32; 4], fraction: f32) -> f32 {
+    let [p0, p1, p2, p3] = values;
+    let (t, t2, t3) = (fraction, fraction * fraction, fraction * fraction * fraction);
+    0.5 * (2. * p1 + (p2 - p0) * t + (2. * p0 - 5. * p1 + 4. * p2 - p3) * t2 + (3. * p1 - p0 - 3. * p2 + p3) * t3)
+}
+
+// A JSON value, as far as camera path files need them. Strings are kept without unescaping, as only keys use them.
+enum Json {
+    Number(f32),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+    Other,
+}
+
+impl Json {
+    // Returns the value of a key of an object, or None for other values and missing keys.
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    // Returns a number's value, or None for other values.
+    fn number(&self) -> Option<f32> {
+        match self {
+            Json::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+// Reads JSON text one value at a time.
+struct JsonParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    // Parses JSON text holding a single value, or returns None if it isn't valid.
+    fn parse(text: &str) -> Option<Json> {
+        let mut parser = JsonParser { text, position: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        (parser.position == text.len()).then_some(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    // Consumes a character after any whitespace if it is the expected one, returning whether it was.
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        let found = self.text[self.position..].starts_with(expected);
+        if found {
+            self.position += expected.len_utf8();
+        }
+        found
+    }
+
+    fn value(&mut self) -> Option<Json> {
+        self.skip_whitespace();
+        let rest = &self.text[self.position..];
+        if self.eat('{') {
+            let mut entries = Vec::new();
+            if self.eat('}') {
+                return Some(Json::Object(entries));
+            }
+            loop {
+                self.skip_whitespace();
+                let key = self.string()?;
+                if !self.eat(':') {
+                    return None;
+                }
+                entries.push((key, self.value()?));
+                if self.eat('}') {
+                    return Some(Json::Object(entries));
+                }
+                if !self.eat(',') {
+                    return None;
+                }
+            }
+        }
+        if self.eat('[') {
+            let mut values = Vec::new();
+            if self.eat(']') {
+                return Some(Json::Array(values));
+            }
+            loop {
+                values.push(self.value()?);
+                if self.eat(']') {
+                    return Some(Json::Array(values));
+                }
+                if !self.eat(',') {
+                    return None;
+                }
+            }
+        }
+        if rest.starts_with('"') {
+            return self.string().map(|_| Json::Other);
+        }
+        for literal in ["true", "false", "null"] {
+            if rest.starts_with(literal) {
+                self.position += literal.len();
+                return Some(Json::Other);
+            }
+        }
+
+        // Anything else has to be a number, which runs until the next delimiter.
+        let length = rest.find(|character: char| !"+-.0123456789eE".contains(character)).unwrap_or(rest.len());
+        let number = rest[..length].parse::<f32>().ok()?;
+        self.position += length;
+        Some(Json::Number(number))
+    }
+
+    // Reads a string, which must start at the current position, skipping over escaped characters without decoding them.
+    fn string(&mut self) -> Option<String> {
+        let rest = self.text[self.position..].strip_prefix('"')?;
+        let mut escaped = false;
+        for (index, character) in rest.char_indices() {
+            match (escaped, character) {
+                (false, '\\') => escaped = true,
+                (false, '"') => {
+                    self.position += index + 2;
+                    return Some(String::from(&rest[..index]));
+                }
+                _ => escaped = false,
+            }
+        }
+        None
+    }
+}
@@ -2,69 +2,561 @@
 use std::*;  // Standard library for basic Rust functionality.
 use process::exit;  // For gracefully exiting the program.
 use time::Duration;  // To manage time durations, like frame rate control.
+use io::IsTerminal;  // To check whether input comes from a terminal.
 use crossterm::{event, execute, terminal, style, cursor};  // For terminal I/O operations (e.g., handling events, changing terminal styles).
 
 // Modules that may include custom logic for screen handling, 3D models, and calculations.
 mod screen;
 mod three;
 mod model;
+mod options;
+mod watch;
+mod sequence;
+mod sheet;
+mod camera_path;
 
 // Configuration constants for viewport and camera settings.
 const VIEWPORT_FOV: f32 = 1.7;  // Field of view for the camera.
 const VIEWPORT_DISTANCE: f32 = 0.1;  // The default distance of the camera from the model.
-const TARGET_DURATION_PER_FRAME: Duration = Duration::from_millis(1000 / 60);  // Target frame duration for 60 FPS.
-const MOUSE_SPEED_MULTIPLIER: f32 = 30.;  // Multiplier to control mouse movement speed.
+const MOUSE_SPEED_MULTIPLIER: f32 = f32::consts::TAU;  // Multiplier to control mouse rotation speed, in radians per screen width dragged.
 const INITIAL_DISTANCE_MULTIPLIER: f32 = 1.5;  // Initial zoom level for camera.
 const SCROLL_MULTIPLIER: f32 = 0.03;  // Zoom in/out factor when scrolling.
-const PAN_MULTIPLIER: f32 = 0.1;  // Factor for panning the camera view.
+const ZOOM_STEP: f32 = 0.1;  // Distance zoomed per key press, relative to the radius of the model's bounding sphere.
+const MINIMUM_DISTANCE_MULTIPLIER: f32 = 1.5;  // Closest the camera can zoom to the center of the model, relative to the viewport distance.
+const PAN_MULTIPLIER: f32 = 1.;  // Factor for panning the camera view, as a fraction of the distance to the orbit center per screen width dragged.
+const PICK_EASING: f32 = 0.25;  // Fraction of the way the orbit center moves towards a picked point each frame.
+const EASING_SETTLED: f32 = 1e-4;  // Movement of the orbit center per frame, relative to the model's diagonal, below which it counts as settled.
+const COARSE_ROTATION_STEP: f32 = f32::consts::PI / 12.;  // Rotation per arrow key press (15 degrees).
+const FINE_ROTATION_STEP: f32 = f32::consts::PI / 180.;  // Rotation per shift + arrow key press (1 degree).
+const MAX_LINE_WIDTH: u16 = 8;  // Thickest lines that can be selected, in screen pixels.
+const CLIP_PLANE_STEP: f32 = 0.02;  // Distance the clip plane moves per key press, relative to the model's diagonal.
+const LIGHT_ROTATION_STEP: f32 = f32::consts::PI / 12.;  // Rotation of the lights per key press (15 degrees).
+const LIGHT_DRAG_STEP: f32 = f32::consts::PI / 60.;  // Rotation of the lights per cell the mouse is dragged across (3 degrees).
+const EXPLODE_STEP: f32 = 0.25;  // Change of the explode factor per key press.
+const FOV_STEP: f32 = f32::consts::PI / 36.;  // Change of the field of view per key press (5 degrees).
+const FOV_LIMITS: (f32, f32) = (f32::consts::PI / 18., f32::consts::PI * 17. / 18.);  // Narrowest and widest field of view (10 and 170 degrees).
+const SENSITIVITY_STEP: f32 = 1.25;  // Factor the rotation or pan speed changes by per key press.
+const SENSITIVITY_LIMITS: (f32, f32) = (0.05, 20.);  // Slowest and fastest rotation and pan speeds, relative to the defaults.
+const INTERACTIVE_EDGE_BUDGET: usize = 20_000;  // Default number of edges drawn while the view is being moved.
+const HEADLESS_DIMENSIONS: (u16, u16) = (80, 24);  // Default size of a headless render in terminal cells.
+const DEFAULT_OPTIONS_VARIABLE: &str = "TERMITE_OPTS";  // Environment variable holding options to apply before the command line's.
+const LARGE_COORDINATE: f32 = 1e5;  // Coordinates beyond which single precision loses visible detail, warned about without "--local-origin".
+const VALIDATION_FAILED_EXIT_CODE: i32 = 1;  // Exit code of "--validate" when the model has problems.
+const CONTACT_SHEET_VIEWS: [&str; 4] = ["front", "right", "top", "iso"];  // Views put on a contact sheet unless "--views" is given.
+const TIMEOUT_EXIT_CODE: i32 = 124;  // Exit code when a headless run times out, the same as the timeout command's.
+const ERROR_EXIT_CODE: i32 = 1;  // Exit code when the options or the model can't be used.
+const HELP_OVERLAY_COLOR: style::Color = style::Color::DarkGrey;  // Background of the help overlay, dimming the render behind it.
+const SIDEBAR_WIDTH: u16 = 24;  // Number of columns on the right of the terminal taken by the sidebar.
+const FOG_LEGEND_WIDTH: usize = 16;  // Number of cells in the fog legend's gradient.
+const FOG_LEGEND_SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];  // Characters shading the fog legend, from hidden to fully visible.
 const HELP_MSG: &str = "\
 \x1b[1mt3d\x1b[0m: Visualize .obj files in the terminal!
 
 \x1b[1mUsage\x1b[0m:
     \"t3d <filepath.obj>\": Interactively view the provided .obj file.
+        Free-form curves (\"curv\") are drawn as straight segments between their control vertices.
+    \"t3d <https://url/model.obj>\": Download and view a .obj file (requires the \"net\" feature).
+        Gzip-compressed .obj files and downloads are read too, whatever they are named (requires the \"gzip\" feature).
+    \"t3d <filepath.3mf>\": Interactively view the provided .3mf file (requires the \"threemf\" feature).
+    \"t3d <directory or files> --sequence\": Play back model files in order, such as the timesteps of a simulation.
+    \"t3d --demo <name>\": View a built-in demo model: grid, cube, tetrahedron, octahedron, icosahedron or icosphere[:subdivisions].
     \"t3d --h\", \"t3d --help\", \"t3d -h\", \"t3d -help\", \"t3d\": Help and info.
     \"t3d --v\", \"t3d --version\", \"t3d -v\", \"t3d -version\": Get version info.
+    \"t3d --list-formats\": List the model formats this build can load, and the features needed for the others.
+
+\x1b[1mOptions\x1b[0m:
+    \"--output-format plain|ansi|html\": Print a single frame as text instead of viewing interactively.
+    \"--export <out.obj>\": Write the model to a .obj file after applying \"--axis\", \"--flip\", \"--subdivide\", \"--recenter\",
+        \"--largest-component\" and \"--fix-winding\", then exit.
+    \"--validate\": Check the model for non-manifold edges, holes, duplicate vertices, degenerate triangles, separate pieces
+        and triangles wound against their neighbors, then exit with code 1 if any were found. The report also counts the zero-length
+        edges removed, which don't fail it. Add \"--json\" to print the report as JSON.
+    \"--export-precision <decimals>\": Number of decimal places of the exported coordinates (default 6).
+    \"--bench <frames>\": Render frames headlessly while rotating and print timing statistics.
+    \"--contact-sheet <out.ppm>\": Render several views of the model headlessly and write them as a grid to a PPM image, then exit.
+    \"--views <names>\": Comma-separated views for the contact sheet, out of front, back, left, right, top, bottom and iso
+        (default front,right,top,iso). Each tile is labeled with its view's name.
+    \"--camera-path <path.json>\": Load a camera path to fly through from this file if it exists, and save the keyframes added
+        and cleared with [K] and [C] to it, so that presentations can be replayed.
+    \"--path-frames <count>\": Print this many frames flying along the camera path instead of viewing interactively, one after
+        another in the format given by \"--output-format\" (default plain), then exit.
+    \"--timeout <seconds>\": Give up on printing, benchmarking, making a contact sheet or exporting after this long, exiting with code 124.
+    \"--width <columns>\", \"--height <rows>\": Size of the printed or benchmarked frames and contact sheet tiles (default 80x24).
+    \"--recenter\": Move the model so its bounding box is centered on the origin.
+    \"--fix-winding\": Turn over triangles wound the other way around from their neighbors, so shading and culling look right.
+        Each piece of the model keeps the winding most of its triangles have, and pieces that can't agree (like a Möbius strip) stay as they are.
+    \"--largest-component\": Keep only the largest connected part of the model, dropping stray geometry that spoils the framing.
+    \"--invert\": Invert the rendered pixels, for terminals with light backgrounds.
+    \"--pixel block|braille|sextant|ascii\": Characters to draw with. Sextants need a font supporting Unicode 13.
+        By default, Braille is used, except for ASCII on terminals unlikely to show Braille: without a UTF-8 locale (LANG),
+        or on the Linux console. Printed frames always default to Braille. If you see boxes instead of the model,
+        use \"--pixel ascii\" (or add it to TERMITE_OPTS), and if ASCII was picked but Braille works, use \"--pixel braille\".
+    \"--line-width <pixels>\": Width of the lines used to draw edges (default 1).
+    \"--fog-start <distance>\", \"--fog-end <distance>\": Fade edges out between these distances from the camera.
+    \"--no-mouse\": Leave the mouse alone and use only the keyboard controls.
+    \"--probe\": Start out showing the coordinates of the vertex under the mouse, as [P] toggles.
+    \"--no-dedup\": Keep the edges of .obj files in file order with their duplicates, such as for exporting or debugging.
+        Edges shared by neighboring faces are then drawn twice and held in memory twice, slowing down big meshes.
+    \"--local-origin\": Move the origin to the center of the model while loading, for coordinates in the millions such as
+        geospatial point clouds, which otherwise render jittery. Shown coordinates are then relative to that center,
+        while exported ones are the file's. Only .obj files are read in full precision.
+    \"--rotate-speed <factor>\", \"--pan-speed <factor>\": How fast dragging rotates and pans, relative to the default (1).
+    \"--pan-step <fraction>\": Distance panned per key press, relative to the radius of the model (default 0.1).
+    \"--axis y-up|z-up\": Which axis points up in the model (default y-up). OBJ and glTF files are usually Y-up,
+        while 3MF, STL and PLY files and most CAD and point cloud tools are Z-up.
+    \"--flip x|y|z\": Mirror the model on an axis, for models exported with the other handedness.
+    \"--clip-plane x|y|z\": Start with a section view cut perpendicular to this axis.
+    \"--yaw <angle>\", \"--pitch <angle>\", \"--roll <angle>\": Initial orientation, in radians or degrees with a \"deg\" suffix.
+    \"--distance <distance>\": Initial distance from the camera to the center of the model.
+    \"--eye x,y,z\", \"--target x,y,z\", \"--up x,y,z\": Place the camera at an eye point looking at a target point, with a direction
+        kept up on screen, instead of giving its orientation and distance. The target defaults to the center of the model, and up to 0,1,0.
+    \"--rotate-about origin|center|<x,y,z>\": Point to orbit around, such as the world origin for models that are placed
+        away from it on purpose (default center, the center of the model's bounding box). [I] returns to it after picking.
+    \"--no-fov-coupling\": Zoom when changing the field of view, instead of moving the camera to keep the model's size.
+    \"--color-mode truecolor|256|none\": How to write colors, detected from the terminal by default.
+    \"--no-color\": Render in the terminal's default color, ignoring material (\"usemtl\" and \"Kd\") colors.
+    \"--quiet\": Don't print warnings, such as about unsupported statements in the model file.
+    \"--face-colors\": Start with every face in its own color, to tell adjacent faces apart.
+    \"--seed <number>\": Seed for picking the face colors, which are the same on every run with the same seed (default 0).
+    \"--ambient <brightness>\": Brightness from 0 to 1 added to every shaded face, so unlit faces aren't black (default 0).
+    \"--light dir:<x,y,z>[,intensity]\": Add a light for shading from this direction, with an intensity from 0 to 1 (default 1).
+        Give it more than once for more lights, whose light adds up. Without any, a key and a fill light follow the camera.
+    \"--light-dir <x,y,z>\": Add a light with an intensity of 1, the same as \"--light dir:<x,y,z>\".
+    \"--explode <factor>\": Push the model's groups (\"g\" and \"o\") apart from its center for an exploded view (default 0).
+    \"--subdivide <iterations>\": Smooth the model's faces, splitting each triangle into four per iteration (at most 4).
+    \"--max-edges <count>\": Draw at most this many evenly spaced edges of large models.
+    \"--interactive-max-edges <count>\": Draw at most this many edges while the view is moving (default 20000).
+    \"--edge-priority spread|length|silhouette\": Which edges to draw when there are too many: evenly spaced ones,
+        the longest on screen, or the model's outline and then the longest (default silhouette).
+    \"--fps <frames>\": Number of files shown per second when playing back a sequence (default 10).
+    \"--max-fps <frames>\": Most frames drawn per second while the view is changing or animating (default 60).
+    \"--max-fps-idle <frames>\": Most frames drawn per second while nothing is changing, to save power (default 5).
+        Input still shows up right away, as it wakes the idle wait.
+    \"--watch\": Reload the model whenever its file changes, keeping the current view.
+
+    Options can also be given in the TERMITE_OPTS environment variable, such as TERMITE_OPTS=\"--pixel block --no-mouse\",
+    to use them every time. Options on the command line take precedence over the ones in TERMITE_OPTS.
 
 \x1b[1mControls\x1b[0m:
     Scroll down to zoom out, scroll up to zoom in.
     Click and drag the mouse to rotate around the model.
     Click and drag the mouse while holding [shift] to pan.
+    Press the arrow keys to rotate in 15 degree steps, hold [shift] for 1 degree steps.
+    Press [+] or [=] to zoom in and [-] to zoom out, in steps relative to the size of the model.
+    Press [ctrl] + arrow keys to pan, in steps relative to the size of the model.
 
-    Press [b] to toggle block mode. 
-    Press [p] to toggle vertices mode. 
+    Press [b] to cycle between braille, block, sextant and ASCII pixels. 
+    Press [p] to cycle between edges, vertices, and both. 
+    Press [o] to toggle hiding vertices behind nearer ones, for dense point clouds. 
+    Press [d] to toggle dithered shading mode. 
+    Press [F] to toggle drawing filled faces that hide what is behind them, in place of the edges and vertices. 
+    Press [h] to toggle hiding the faces of closed models turned away from the camera, and the edges between them. 
+    Press [l] and [L] to rotate the lights around the model. 
+    Press [a] to toggle dragging the lights around the model instead of the camera, with a marker for each light. 
+    Press [space] to pause or resume a sequence, and [<] and [>] to step through it. 
+    Press [f] to toggle mirroring the model. 
+    Press [r] to toggle giving every face its own color. 
+    Press [m] to highlight boundary edges in red and non-manifold edges in yellow. 
+    Press [x] to toggle the model's bounding box. 
+    Press [e] and [E] to explode the model's groups further apart or bring them back together. 
+    Press [v] and [V] to narrow or widen the field of view, keeping the model the same size. 
+    Press [s] and [S] to make rotating with the mouse faster or slower, and [t] and [T] to do the same for panning. 
+    Press [i] to toggle picking, where clicking the model makes the camera orbit around the clicked point. 
+    Press [I] to orbit around the center of the model (or the point given by \"--rotate-about\") again. 
+    Press [P] to toggle showing the coordinates of the vertex under the mouse on the status line. 
+    Press [tab] to select the next of the model's groups (\"g\" and \"o\"), and [z] to frame the selected group, or the whole model if none is. 
+    Press [c] to toggle the camera angles overlay. 
+    Press [w] to toggle a sidebar listing the model's groups and materials. While it is open, press [up] and [down] to select
+        one of them and [enter] to hide or show its edges and faces. 
+    Press [g] to toggle the fog legend. 
+    Press [K] to add the current view to the camera path as a keyframe, [C] to clear the path, and [R] to fly along it or stop. 
+    Press [?] or [F1] to show these controls, and [?] or [esc] to hide them. 
+    Press [n] to toggle inverted (light terminal) mode. 
+    Press [ and ] to make lines thinner or thicker. 
+    Press [k] to cycle the section view's clip plane between the x, y and z axes and off. 
+    Press [,] and [.] to move the clip plane. 
 ";
 
-// Function to gracefully close the program by restoring terminal settings.
-fn graceful_close() -> ! {
-    execute!(
+// Whether the interactive session took over the terminal, so that closing only restores it if needed.
+// Headless runs never take it over, which keeps their standard output free of anything but the rendered output.
+static TERMINAL_TAKEN: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(false);
+
+// Whether mouse capture was enabled, so that closing only disables it if needed.
+static MOUSE_CAPTURED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(false);
+
+// Whether non-fatal warnings are suppressed.
+static QUIET: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(false);
+
+// Function to build the clip plane for a section view, perpendicular to a world axis and offset from the center along it.
+fn clip_plane(axis: Option<usize>, center: &three::Point, offset: f32) -> Option<three::ClipPlane> {
+    let axis = axis?;
+    let normal = three::Point::new(
+        if axis == 0 { 1. } else { 0. },
+        if axis == 1 { 1. } else { 0. },
+        if axis == 2 { 1. } else { 0. },
+    );
+    let point = three::Point::new(
+        center.x + normal.x * offset,
+        center.y + normal.y * offset,
+        center.z + normal.z * offset,
+    );
+    Some(three::ClipPlane { point, normal })
+}
+
+// Function to build the model as it is shown, without its hidden groups and materials and with its groups pushed apart for an exploded view.
+// Returns None when nothing is hidden or exploded, and the model is shown as it is.
+fn shown_model(model: &model::Model, hidden: (&[usize], &[usize]), explode: f32) -> Option<model::Model> {
+    let hiding = !hidden.0.is_empty() || !hidden.1.is_empty();
+    let visible = if hiding { Some(model.without_hidden(hidden.0, hidden.1)) } else { None };
+    if explode == 0. {
+        return visible;
+    }
+    Some(visible.as_ref().unwrap_or(model).exploded(explode))
+}
+
+// Function to restore the terminal settings changed for the interactive session.
+// Errors are ignored, as this also runs while panicking, when there is nothing better to do.
+fn restore_terminal() {
+    if !TERMINAL_TAKEN.load(sync::atomic::Ordering::Relaxed) {
+        return;
+    }
+    let _ = execute!(
         io::stdout(),
         cursor::Show,  // Make the cursor visible again.
-        event::DisableMouseCapture,  // Disable mouse capture in the terminal.
-    ).unwrap();
-    terminal::disable_raw_mode().unwrap();  // Restore terminal's original mode.
+    );
+    if MOUSE_CAPTURED.load(sync::atomic::Ordering::Relaxed) {
+        let _ = execute!(
+            io::stdout(),
+            event::DisableMouseCapture,  // Disable mouse capture in the terminal, if it was enabled.
+        );
+    }
+    let _ = terminal::disable_raw_mode();  // Restore terminal's original mode.
+}
+
+// Function to gracefully close the program by restoring terminal settings.
+fn graceful_close() -> ! {
+    restore_terminal();
     exit(0);  // Exit the program.
 }
 
-// Function to close the program with an error message.
+// Function to close the program with an error message, exiting with an error code so that scripts can tell it failed.
 fn error_close(msg: &dyn fmt::Display) -> ! {
     execute!(
         io::stderr(),
         style::Print(msg)  // Print the error message to standard error.
     ).unwrap();
-    graceful_close();  // Close the program after error.
+    restore_terminal();
+    exit(ERROR_EXIT_CODE);  // Exit the program after the error.
+}
+
+// Function to print a non-fatal warning to standard error, unless warnings are suppressed.
+// Diagnostics never go to standard output, which is kept for the rendered output.
+fn warn(msg: &dyn fmt::Display) {
+    if !QUIET.load(sync::atomic::Ordering::Relaxed) {
+        execute!(
+            io::stderr(),
+            style::Print(format!("Warning: {}\n", msg))
+        ).unwrap();
+    }
+}
+
+// Function to format the camera overlay, with angles in degrees and distance in model units.
+fn camera_overlay_text(yaw: f32, pitch: f32, roll: f32, distance: f32, fov: f32, lights: &[three::Light], speeds: (f32, f32)) -> String {
+    let lights = match lights {
+        [] => String::from("camera"),
+        lights => lights
+            .iter()
+            .map(|light| format!("{:.2}, {:.2}, {:.2} ×{:.2}", light.direction.x, light.direction.y, light.direction.z, light.intensity))
+            .collect::<Vec<_>>()
+            .join("; "),
+    };
+    format!(
+        "yaw {:>6.1}° pitch {:>6.1}° roll {:>6.1}° distance {:>8.2} fov {:>5.1}° lights {} rotate ×{:.2} pan ×{:.2}",
+        yaw.to_degrees(), pitch.to_degrees(), roll.to_degrees(), distance, fov.to_degrees(), lights, speeds.0, speeds.1
+    )
+}
+
+// Function to format what the last frame drew, as edges and triangles drawn out of those considered, and pixels written,
+// followed by the number of zero-length edges dropped from the model, which never reach the renderer.
+fn render_stats_text(stats: &three::RenderStats, removed_zero_length_edges: usize) -> String {
+    format!(
+        "edges {}/{} triangles {}/{} pixels {} zero-length edges removed {}",
+        stats.edges_drawn, stats.edges_considered, stats.triangles_drawn, stats.triangles_considered, stats.pixels_written,
+        removed_zero_length_edges
+    )
+}
+
+// Function to format the fog legend: a gradient of how visible edges are from the fog's start distance to its end distance.
+// The gradient is sampled with the same fog intensity as the edges, and is reversed in inverted mode like the pixels are.
+fn fog_legend_text(fog: (f32, f32), inverted: bool) -> String {
+    let gradient: String = (0..FOG_LEGEND_WIDTH).map(|cell| {
+        let depth = fog.0 + (fog.1 - fog.0) * (cell as f32 + 0.5) / FOG_LEGEND_WIDTH as f32;
+        let mut intensity = three::fog_intensity(depth, fog);
+        if inverted { intensity = 1. - intensity }
+        FOG_LEGEND_SHADES[(intensity * (FOG_LEGEND_SHADES.len() - 1) as f32).round() as usize]
+    }).collect();
+    format!("near {:.2} {} {:.2} far", fog.0, gradient, fog.1)
+}
+
+// Function to list the controls shown by the help overlay, taken from the help message so that the two always agree.
+fn controls_lines() -> Vec<&'static str> {
+    match HELP_MSG.split_once("Controls\x1b[0m:") {
+        Some((_, controls)) => controls.lines().map(str::trim).filter(|line| !line.is_empty()).collect(),
+        None => Vec::new(),
+    }
+}
+
+// Function to stamp the help overlay onto the top-left corner of a screen, listing the controls and then the modes that are on.
+// The overlay stops above the status line, and its background is dimmed, or reversed when colors are off.
+fn draw_help_overlay(screen: &mut screen::Screen, active_modes: &[&str]) {
+    let modes = if active_modes.is_empty() { String::from("On: nothing") } else { format!("On: {}", active_modes.join(", ")) };
+    let mut lines = vec!["Controls (press [?] or [esc] to close)"];
+    lines.extend(controls_lines());
+    lines.push(&modes);
+
+    let (columns, rows) = terminal::size().unwrap_or(screen::DEFAULT_TERMINAL_DIMENSIONS);
+    let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0).min(columns as usize);
+    let height = (lines.len() as u16).min(rows.saturating_sub(1));
+    let overlay_style = screen::OverlayStyle {
+        background: Some(HELP_OVERLAY_COLOR),
+        reverse: screen.color_mode == screen::ColorMode::Monochrome,
+        ..screen::OverlayStyle::default()
+    };
+    let padded: Vec<String> = lines.iter().take(height as usize).map(|line| {
+        let mut text: String = line.chars().take(width).collect();
+        text.extend(iter::repeat_n(' ', width - text.chars().count()));
+        text
+    }).collect();
+    screen.blit_text((0, 0), &padded.iter().map(String::as_str).collect::<Vec<_>>(), overlay_style);
+}
+
+// Function to stamp the sidebar onto the reserved columns on the right of the terminal, above the status line.
+// It lists the model's groups and then its materials with swatches of their colors, ticking the ones that are shown,
+// and highlights the selected entry, counting the groups first and then the materials. The list scrolls to keep the selection in view.
+fn draw_sidebar(screen: &mut screen::Screen, model: &model::Model, hidden: (&[usize], &[usize]), selected: usize) {
+    let (columns, rows) = terminal::size().unwrap_or(screen::DEFAULT_TERMINAL_DIMENSIONS);
+    let width = SIDEBAR_WIDTH.min(columns) as usize;
+    let height = rows.saturating_sub(1) as usize;
+
+    // Each line has its text, its swatch color if any, and whether it is the selected entry.
+    let mut lines = Vec::<(String, Option<style::Color>, bool)>::new();
+    let checkbox = |hidden: &[usize], index: usize| if hidden.contains(&index) { "[ ]" } else { "[x]" };
+    lines.push((format!("Groups ({})", model.groups.len()), None, false));
+    for (index, name) in model.groups.iter().enumerate() {
+        lines.push((format!("{} {}", checkbox(hidden.0, index), name), None, selected == index));
+    }
+    lines.push((format!("Materials ({})", model.materials.len()), None, false));
+    for (index, material) in model.materials.iter().enumerate() {
+        let swatch = three::terminal_color(material.color);
+        lines.push((format!("{} {}", checkbox(hidden.1, index), material.name), swatch, selected == model.groups.len() + index));
+    }
+
+    let selected_line = lines.iter().position(|line| line.2).unwrap_or(0);
+    let first = (selected_line + 1).saturating_sub(height);
+    let left = columns.saturating_sub(width as u16);
+    for row in 0..height {
+        let (text, swatch, highlighted) = lines.get(first + row).cloned().unwrap_or_default();
+        let mut text: String = text.chars().take(width.saturating_sub(3)).collect();
+        text.extend(iter::repeat_n(' ', width.saturating_sub(3) - text.chars().count()));
+        let text_style = screen::OverlayStyle { reverse: highlighted, ..screen::OverlayStyle::default() };
+        screen.blit_text((left, row as u16), &[&format!(" {}", text)], text_style);
+
+        // Materials get a swatch of their color at the end of the line, and everything else blank cells.
+        // Swatches are left blank when colors are off, since they would only show the terminal's default color.
+        let end = left.saturating_add(text.chars().count() as u16 + 1);
+        match swatch.filter(|_| screen.color_mode != screen::ColorMode::Monochrome) {
+            Some(color) => screen.blit_text((end, row as u16), &["██"], screen::OverlayStyle { foreground: Some(color), ..screen::OverlayStyle::default() }),
+            None => screen.blit_text((end, row as u16), &["  "], screen::OverlayStyle::default()),
+        }
+    }
+}
+
+// Function to find the screen pixel in the middle of a terminal cell, for finding what the mouse is over.
+fn cell_center_pixel(pixel_mode: screen::PixelMode, column: u16, row: u16) -> screen::Point {
+    let (cell_width, cell_height) = pixel_mode.cell_size();
+    screen::Point::new(
+        column as i32 * cell_width as i32 + cell_width as i32 / 2,
+        row as i32 * cell_height as i32 + cell_height as i32 / 2,
+    )
+}
+
+// Function to pan the view by distances along the camera's right and up directions, for both the mouse and the keyboard.
+// The camera moves along with the orbit center, and so does the point being eased towards, so that the easing doesn't pull the view back.
+fn pan_view(camera: &mut three::Camera, center: &mut three::Point, orbit_target: &mut three::Point, right: f32, up: f32) {
+    let offset = camera.pan_offset(right, up);
+    camera.coordinates = camera.coordinates + offset;
+    *center = *center + offset;
+    *orbit_target = *orbit_target + offset;
+}
+
+// Function to find the distance at which a sphere of a given radius looks as big with a new field of view as it did with the old one.
+// Distances inside the sphere can't keep its size, so they are scaled as if the sphere were far away.
+fn distance_for_fov(distance: f32, radius: f32, old_fov: f32, new_fov: f32) -> f32 {
+    let ratio = (new_fov / 2.).tan() / (old_fov / 2.).tan();
+    if distance <= radius {
+        return distance / ratio;
+    }
+
+    // The sphere's silhouette spans tan(asin(radius / distance)) of the view plane, relative to tan(fov / 2) for the whole view.
+    let silhouette = (radius / distance).asin().tan() * ratio;
+    radius / silhouette.atan().sin()
+}
+
+// Function to format the status line, listing what is shown, how it is drawn and which optional modes are on.
+fn status_line_text(name: &str, pixel_mode: screen::PixelMode, display_mode: three::DisplayMode, active_modes: &[&str]) -> String {
+    let mut text = format!(" {} | {} | {}", name, pixel_mode.name(), display_mode.name());
+    if !active_modes.is_empty() {
+        text.push_str(&format!(" | {}", active_modes.join(", ")));
+    }
+    text
+}
+
+// Function to format the report of "--validate", as lines of counts or as a JSON object.
+fn validation_text(validation: &model::Validation, json: bool) -> String {
+    let counts = [
+        ("non_manifold_edges", "Non-manifold edges", validation.non_manifold_edges),
+        ("boundary_edges", "Boundary edges", validation.boundary_edges),
+        ("duplicate_vertices", "Duplicate vertices", validation.duplicate_vertices),
+        ("degenerate_triangles", "Degenerate triangles", validation.degenerate_triangles),
+        ("components", "Connected components", validation.components),
+        ("flipped_triangles", "Flipped triangles", validation.flipped_triangles),
+        ("non_orientable_pieces", "Non-orientable pieces", validation.non_orientable_pieces),
+        ("removed_zero_length_edges", "Removed zero-length edges", validation.removed_zero_length_edges),
+    ];
+    if json {
+        let fields: Vec<String> = counts.iter().map(|(key, _, count)| format!("\"{}\": {}", key, count)).collect();
+        return format!("{{{}, \"valid\": {}}}", fields.join(", "), !validation.has_problems());
+    }
+    counts.iter().map(|(_, name, count)| format!("{}: {}", name, count)).collect::<Vec<_>>().join("\n")
+}
+
+// Function to format the list of model formats and their extensions, noting the ones this build can't load.
+fn formats_text() -> String {
+    let mut text = String::new();
+    for format in model::FORMATS.iter() {
+        let extensions: Vec<String> = format.extensions.iter().map(|extension| format!(".{}", extension)).collect();
+        text.push_str(&format!("{} ({})", format.name, extensions.join(", ")));
+        if let (false, Some(feature)) = (format.available, format.feature) {
+            text.push_str(&format!(": requires building with the \"{}\" feature", feature));
+        }
+        text.push('\n');
+    }
+
+    // Compressed files are recognized by their content whatever they are named, but only .obj files are read as a stream.
+    text.push_str(&format!("Gzip-compressed {} (.{}.gz, or any other name)", model::FORMATS[0].name, model::FORMATS[0].extensions[0]));
+    if !model::GZIP_AVAILABLE {
+        text.push_str(": requires building with the \"gzip\" feature");
+    }
+    text.push('\n');
+    text
+}
+
+// Function to load a model from a file path, or from an HTTP(S) URL when built with the "net" feature.
+// Duplicate edges are removed from .obj files if dedup is true, and the origin is moved to the model's center if local_origin is.
+fn load_model(file_path: &str, dedup: bool, local_origin: bool) -> Result<model::Model, Box<dyn error::Error>> {
+    let position = three::Point::new(0., 0., 0.);
+    if file_path.starts_with("http://") || file_path.starts_with("https://") {
+        #[cfg(feature = "net")]
+        return model::Model::new_obj_from_url(file_path, position, dedup, local_origin);
+
+        #[cfg(not(feature = "net"))]
+        return Err(Box::from("Loading models from URLs requires building with the \"net\" feature."));
+    }
+    model::Model::new_from_extension(file_path, position, dedup, local_origin)
+}
+
+// Function to get the last component of a path or URL, for showing which file is loaded.
+fn file_name(file_path: &str) -> String {
+    match file_path.trim_end_matches('/').rsplit('/').next() {
+        Some(name) if !name.is_empty() => String::from(name),
+        _ => String::from(file_path),
+    }
+}
+
+// Function to apply the requested processing to a freshly loaded model, dropping stray parts, fixing its winding, subdividing,
+// mirroring and then recentering it.
+fn prepare_model(model: &mut model::Model, options: &options::Options, flip_axis: Option<usize>) {
+    if options.largest_component {
+        // Drop all but the largest part of the model, reporting what was dropped along with the loading warnings.
+        let components = model.keep_largest_component();
+        if components > 1 {
+            model.warnings.push(format!("Found {} connected components, dropped {} of them.", components, components - 1));
+        }
+    }
+    if options.fix_winding {
+        // Make neighboring triangles agree on their winding, reporting how many were turned over along with the loading warnings.
+        let fixed = model.fix_winding();
+        if fixed > 0 {
+            model.warnings.push(format!("Turned over {} triangle(s) wound against their neighbors.", fixed));
+        }
+    }
+    if options.z_up { model.z_up_to_y_up() }  // Stand models made with Z pointing up upright.
+    if let Some(iterations) = options.subdivide { model.subdivide(iterations) }  // Smooth coarse meshes if requested.
+    if let Some(axis) = flip_axis { model.flip(axis) }  // Mirror models exported in the other handedness.
+    if options.recenter { model.center_to_origin() }  // Move the model onto the origin if requested.
+    let removed = model.remove_zero_length_edges();  // Drop edges that have become zero-length, or came from formats that don't drop them.
+    if removed > 0 { model.warnings.push(format!("Removed {} zero-length edge(s).", removed)) }
+    if options.edge_priority == three::EdgePriority::Silhouette { model.cache_edge_triangles() }  // Find the outline when decimating.
+}
+
+// Function to build one of the built-in demo models by name.
+fn demo_model(name: &str) -> Result<model::Model, Box<dyn error::Error>> {
+    let position = three::Point::new(0., 0., 0.);
+
+    // Demos can take a parameter after a colon, such as the number of subdivisions in "icosphere:3".
+    let (name, parameter) = match name.split_once(':') {
+        Some((name, parameter)) => (name, Some(parameter)),
+        None => (name, None),
+    };
+
+    match (name, parameter) {
+        ("grid", None) => Ok(model::Model::new_grid(10., 10, position)),
+        ("cube", None) => Ok(model::Model::new_cube(1., position)),
+        ("tetrahedron", None) => Ok(model::Model::new_tetrahedron(1., position)),
+        ("octahedron", None) => Ok(model::Model::new_octahedron(1., position)),
+        ("icosahedron", None) => Ok(model::Model::new_icosahedron(1., position)),
+        ("icosphere", None) => Ok(model::Model::new_icosphere(1., 2, position)),
+        ("icosphere", Some(subdivisions)) => Ok(model::Model::new_icosphere(1., subdivisions.parse()?, position)),
+        _ => Err(Box::from(format!(
+            "Unknown demo \"{}\", expected grid, cube, tetrahedron, octahedron, icosahedron or icosphere[:subdivisions].",
+            name
+        ))),
+    }
 }
 
 fn main() {
+    // Restore the terminal before printing the panic message if anything panics, so the shell isn't left in raw mode.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+
     // Parse command-line arguments.
     let args: Vec<String> = env::args().collect();  // Collect arguments into a vector.
-    if args.len() > 2 { error_close(&"Please supply only one file path to visualize.") }  // Error if more than one argument.
     if args.is_empty() { error_close(&"Error parsing arguments.") }  // Error if no arguments.
+    let defaults = env::var(DEFAULT_OPTIONS_VARIABLE).unwrap_or_default();  // Options to use every time, if set.
+    let options = match options::Options::parse_with_defaults(&defaults, &args) {
+        Ok(options) => options,
+        Err(error) => error_close(&error)  // Error on unknown options or more than one file path.
+    };
+    QUIET.store(options.quiet, sync::atomic::Ordering::Relaxed);
 
-    // If the user requested help, display help message.
-    let help_mode = args.len() == 1 || 
-        ["-h", "-help", "--h", "--help"].map(String::from).contains(&args[1]);
+    // Give up on headless runs that take too long, such as in scripts, cleaning up the terminal like any other exit.
+    if let Some(timeout) = options.timeout {
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs_f32(timeout));
+            restore_terminal();
+            let _ = execute!(
+                io::stderr(),
+                style::Print(format!("Timed out after {} seconds.\n", timeout))
+            );
+            exit(TIMEOUT_EXIT_CODE);
+        });
+    }
 
-    if help_mode {
+    // If the user requested help, display help message.
+    if options.help {
         execute!(
             io::stdout(),
             style::Print(HELP_MSG)  // Print the help message.
@@ -73,10 +565,7 @@ fn main() {
     }
 
     // If the user requested version information, display the version.
-    let version_mode = args.len() == 1 || 
-        ["-v", "-version", "--v", "--version"].map(String::from).contains(&args[1]);
-
-    if version_mode {
+    if options.version {
         execute!(
             io::stdout(),
             style::Print(env!("CARGO_PKG_VERSION"))  // Print the program's version.
@@ -84,22 +573,84 @@ fn main() {
         graceful_close();  // Close the program after displaying the version.
     }
 
-    // Enable raw terminal mode (no line buffering, etc.) and hide the cursor for the interactive session.
-    terminal::enable_raw_mode().unwrap();
-    execute!(
-        io::stdout(),
-        cursor::Hide,  // Hide the cursor.
-        event::EnableMouseCapture,  // Enable mouse tracking.
-    ).unwrap();
+    // If the user requested the supported formats, list them.
+    if options.list_formats {
+        execute!(
+            io::stdout(),
+            style::Print(formats_text())  // Print the formats and their extensions.
+        ).unwrap();
+        graceful_close();  // Close the program after listing the formats.
+    }
 
-    // Get the file path of the .obj file to visualize.
-    let file_path = &args[1];
-    
-    // Attempt to load the model from the specified .obj file.
-    let input_model = match model::Model::new_obj(file_path, three::Point::new(0., 0., 0.)) {
+    // Collect the frames of the sequence if one was requested, which starts from its first frame.
+    let mut sequence = match options.sequence {
+        true => match sequence::Sequence::new(&options.file_paths, options.fps) {
+            Ok(sequence) => Some(sequence),
+            Err(error) => error_close(&error),
+        },
+        false => None,
+    };
+    let file_path = match &sequence {
+        Some(sequence) => sequence.paths.first(),
+        None => options.file_paths.first(),
+    };
+
+    // Attempt to build the requested demo model, or load the model from the specified .obj file (downloading it if given a URL).
+    let loaded_model = match (&options.demo, file_path) {
+        (Some(_), Some(_)) => error_close(&"Please supply either a demo or a file path, not both."),
+        (Some(demo), None) => demo_model(demo),
+        (None, Some(file_path)) => load_model(file_path, !options.no_dedup, options.local_origin),
+        (None, None) => error_close(&"Please supply a file path to visualize."),
+    };
+    let mut input_model = match loaded_model {
         Ok(model) => model,  // If successful, continue.
         Err(error) => error_close(&error)  // If error occurs, show error and exit.
     };
+    let mut flip_axis = options.flip;
+    prepare_model(&mut input_model, &options, flip_axis);
+    for warning in input_model.warnings.iter() { warn(warning) }  // Report anything skipped while loading.
+    if input_model.edges.is_empty() && input_model.points.is_empty() { warn(&"The model has nothing to draw.") }
+    let (low, high) = input_model.world_bounds();
+    let largest_coordinate = [low.x, low.y, low.z, high.x, high.y, high.z].iter().fold(0_f32, |largest, value| largest.max(value.abs()));
+    if !options.local_origin && largest_coordinate > LARGE_COORDINATE {
+        warn(&format!("Coordinates reach {:.0}, too far from the origin to show fine detail. Try \"--local-origin\".", largest_coordinate));
+    }
+    if options.explode > 0. && input_model.groups.is_empty() { warn(&"The model has no groups to explode.") }
+
+    // In validation mode, report the problems found in the prepared model instead of viewing it, failing if there are any.
+    if options.json && !options.validate { error_close(&"\"--json\" can only be used with \"--validate\".") }
+    if options.validate {
+        let validation = input_model.validate();
+        println!("{}", validation_text(&validation, options.json));
+        exit(if validation.has_problems() { VALIDATION_FAILED_EXIT_CODE } else { 0 });
+    }
+
+    // In export mode, write the prepared model to a file instead of viewing it.
+    if let Some(export_path) = &options.export {
+        let written = fs::File::create(export_path)
+            .and_then(|file| input_model.write_obj(&mut io::BufWriter::new(file), options.export_precision));
+        if let Err(error) = written {
+            error_close(&format!("Could not write \"{}\": {}", export_path, error));
+        }
+        exit(0);
+    }
+
+    // Name of what is being visualized, for the status line.
+    let model_name = match (&options.demo, file_path) {
+        (Some(demo), _) => format!("demo {}", demo),
+        (None, Some(file_path)) => file_name(file_path),
+        (None, None) => String::new(),
+    };
+
+    // Watch the model's file for changes if requested, which only makes sense for a single file on disk.
+    let mut watcher = match (options.watch, file_path) {
+        (false, _) => None,
+        (true, _) if sequence.is_some() => error_close(&"\"--watch\" can't be used with \"--sequence\"."),
+        (true, Some(file_path)) if !file_path.starts_with("http://") && !file_path.starts_with("https://") => {
+            Some(watch::FileWatcher::new(file_path))
+        }
+        (true, _) => error_close(&"\"--watch\" requires the path of a model file on disk."),
+    };
 
     // Calculate the center and diagonal of the model's bounding box.
     let bounds = input_model.world_bounds();
@@ -113,23 +664,239 @@ fn main() {
         (bounds.0.y - bounds.1.y).powi(2) +
         (bounds.0.z - bounds.1.z).powi(2)
     ).sqrt();  // Diagonal distance to determine zoom level.
+    let model_center = center;  // Center of the bounding box, which the camera can return to orbiting around.
 
-    // Set up the camera with the initial position and settings.
-    let mut camera = three::Camera::new(
-        center, 
-        0., 0., 0.,  // Initial camera orientation (yaw, pitch, roll).
-        VIEWPORT_DISTANCE, VIEWPORT_FOV,  // Initial camera distance and FOV.
-    );
+    // Orbit around the requested point instead of the center of the model, unless the eye's target already says where to.
+    if let Some((x, y, z)) = options.rotate_about {
+        if options.eye.is_some() { error_close(&"\"--rotate-about\" can't be used with \"--eye\", whose target is orbited around.") }
+        center = three::Point::new(x, y, z);
+    }
+
+    // Set up the camera with the initial position and settings, or looking from an eye point at a target point if requested.
+    // The camera then orbits around the target, at the distance of the eye from it.
+    let point = |(x, y, z): (f32, f32, f32)| three::Point::new(x, y, z);
+    let mut camera = match options.eye.map(point) {
+        None if options.target.is_some() || options.up.is_some() => error_close(&"\"--target\" and \"--up\" require \"--eye\"."),
+        None => three::Camera::new(
+            center, 
+            0., 0., 0.,  // Initial camera orientation (yaw, pitch, roll).
+            VIEWPORT_DISTANCE, VIEWPORT_FOV,  // Initial camera distance and FOV.
+        ),
+        Some(_) if options.yaw.is_some() || options.pitch.is_some() || options.roll.is_some() || options.distance.is_some() => {
+            error_close(&"\"--eye\" can't be used with \"--yaw\", \"--pitch\", \"--roll\" or \"--distance\".")
+        }
+        Some(eye) => {
+            let target = options.target.map_or(center, point);
+            let up = options.up.map_or(three::Point::new(0., 1., 0.), point);
+            if (eye - target).length() == 0. {
+                error_close(&"\"--eye\" and \"--target\" must be different points.");
+            }
+            if up.normalized().cross(&(target - eye).normalized()).length() < 1e-4 {
+                error_close(&"\"--up\" can't be parallel to the direction from \"--eye\" to \"--target\".");
+            }
+            center = target;
+            three::Camera::from_eye_target_up(eye, target, up, VIEWPORT_DISTANCE, VIEWPORT_FOV)
+        }
+    };
+    let orbit_home = center;  // Point orbited around at startup, which the camera can return to after picking.
+    camera.screen.inverted = options.invert;  // Start inverted if requested.
+    camera.screen.color_mode = options.color_mode.unwrap_or_else(screen::ColorMode::detect);  // Use the colors the terminal supports.
+    camera.line_width = options.line_width.clamp(1, MAX_LINE_WIDTH);  // Start with the requested line width.
+    if camera.line_width != options.line_width {
+        warn(&format!("Line width {} is out of range, using {} instead.", options.line_width, camera.line_width));
+    }
+    camera.fog = options.fog_end.map(|end| (options.fog_start.unwrap_or(0.), end));  // Fade edges with distance if requested.
+    camera.edge_budget = options.max_edges;  // Decimate very large models if requested.
+    camera.edge_priority = options.edge_priority;  // Keep the edges that matter most when decimating.
+    camera.face_color_seed = if options.face_colors { Some(options.seed) } else { None };  // Color faces for debugging if requested.
+    camera.ambient = options.ambient;  // Keep unlit faces visible if requested.
+    camera.lights = options.lights
+        .iter()
+        .map(|&((x, y, z), intensity)| three::Light { direction: three::Point::new(x, y, z).normalized(), intensity })
+        .collect();  // Light from fixed directions if requested.
+
+    // Set up the section view, with the clip plane starting at the center of the model.
+    let mut clip_axis = options.clip_axis;
+    let mut clip_offset: f32 = 0.;
+    camera.clip_plane = clip_plane(clip_axis, &center, clip_offset);
+
+    // Initialize camera control variables (yaw, pitch, roll, zoom level), starting from any requested orientation.
+    let mut view_yaw: f32 = options.yaw.unwrap_or(camera.yaw);
+    let mut view_pitch: f32 = options.pitch.unwrap_or(camera.pitch);
+    let mut view_roll: f32 = options.roll.unwrap_or(camera.roll);
+    let mut distance_to_model = match options.eye {
+        Some(_) => (camera.coordinates - center).length(),  // Distance from the requested eye to the target.
+        None => options.distance.unwrap_or(diagonal * INITIAL_DISTANCE_MULTIPLIER),  // Distance scaled by model size.
+    };
+
+    // Apply the initial orientation, keeping the view angles in sync with the clamped camera angles.
+    camera.set_angles(view_yaw, view_pitch, view_roll);
+    view_yaw = camera.yaw;
+    view_pitch = camera.pitch;
+
+    // Options for headless rendering (benchmarks and text output), which never queries the terminal.
+    let mut headless_options = three::RenderOptions::new();
+    headless_options.pixel_mode = options.pixel_mode.unwrap_or(screen::PixelMode::Braille);
+    headless_options.headless = Some((
+        options.width.unwrap_or(HEADLESS_DIMENSIONS.0),
+        options.height.unwrap_or(HEADLESS_DIMENSIONS.1),
+        options.output_format.unwrap_or(screen::OutputFormat::Plain),
+    ));
+
+    // The model as headless rendering draws it, exploded once up front as nothing changes it later.
+    let headless_model = shown_model(&input_model, (&[], &[]), options.explode);
+    let headless_model = headless_model.as_ref().unwrap_or(&input_model);
+
+    // In benchmark mode, render frames into memory while spinning around the model, then report how long they took.
+    if let Some(frames) = options.bench {
+        let frames = frames.max(1);
+        let mut durations = Vec::<Duration>::new();
+        let mut drawn = three::RenderStats::default();  // What the frames drew, added up.
+        for frame in 0..frames {
+            let start = time::Instant::now();
+            camera.set_angles(view_yaw + frame as f32 * f32::consts::TAU / frames as f32, view_pitch, view_roll);
+            camera.orbit(&center, distance_to_model);
+            let (_, stats) = camera.render_frame(headless_model, &headless_options);
+            durations.push(start.elapsed());
+            drawn.edges_drawn += stats.edges_drawn;
+            drawn.edges_considered += stats.edges_considered;
+            drawn.triangles_drawn += stats.triangles_drawn;
+            drawn.triangles_considered += stats.triangles_considered;
+            drawn.pixels_written += stats.pixels_written;
+        }
+
+        let total: Duration = durations.iter().sum();
+        let summary = input_model.summary();
+        println!(
+            "Rendered {} frames of {} points, {} edges and {} triangles, after removing {} zero-length edges, in {:.2?} (average {:.2?}, min {:.2?}, max {:.2?}).",
+            frames,
+            summary.points,
+            summary.edges,
+            summary.triangles,
+            summary.removed_zero_length_edges,
+            total,
+            total / frames,
+            durations.iter().min().unwrap(),
+            durations.iter().max().unwrap(),
+        );
+        println!(
+            "Drew {} of {} edges and {} of {} triangles, writing {} pixels, per frame on average.",
+            drawn.edges_drawn / frames as usize,
+            drawn.edges_considered / frames as usize,
+            drawn.triangles_drawn / frames as usize,
+            drawn.triangles_considered / frames as usize,
+            drawn.pixels_written / frames as usize,
+        );
+        exit(0);
+    }
 
-    // Initialize camera control variables (yaw, pitch, zoom level).
-    let mut view_yaw: f32 = 0.0;
-    let mut view_pitch: f32 = 0.0;
-    let mut distance_to_model = diagonal * INITIAL_DISTANCE_MULTIPLIER;  // Distance scaled by model size.
+    // In contact sheet mode, render each requested view into memory and write them all to one image.
+    if let Some(sheet_path) = &options.contact_sheet {
+        let views = options.views.clone().unwrap_or_else(|| CONTACT_SHEET_VIEWS.iter().map(|view| String::from(*view)).collect());
+        let mut tiles = Vec::<sheet::Tile>::new();
+        for view in views.iter() {
+            let (yaw, pitch) = sheet::view_angles(view).unwrap();  // Names were checked when parsing the options.
+            camera.set_angles(yaw, pitch, 0.);
+            camera.orbit(&center, distance_to_model);
+            camera.render_frame(headless_model, &headless_options);
+            tiles.push(sheet::Tile::new(&camera.screen, view));
+        }
+
+        let written = fs::File::create(sheet_path)
+            .and_then(|file| sheet::write_contact_sheet(tiles, &mut io::BufWriter::new(file)));
+        if let Err(error) = written {
+            error_close(&format!("Could not write \"{}\": {}", sheet_path, error));
+        }
+        exit(0);
+    }
+
+    // Load the camera path if its file exists, or start an empty one that keyframes can be added to.
+    let mut camera_path = match &options.camera_path {
+        Some(path) if path::Path::new(path).exists() => match camera_path::CameraPath::load(path) {
+            Ok(camera_path) => camera_path,
+            Err(error) => error_close(&error),
+        },
+        _ => camera_path::CameraPath::new(),
+    };
+
+    // In path mode, print frames at even intervals along the camera path, from its first keyframe to its last.
+    if let Some(frames) = options.path_frames {
+        if camera_path.keyframes.is_empty() { error_close(&"The camera path has no keyframes.") }
+        let frames = frames.max(1);
+        for frame in 0..frames {
+            let seconds = camera_path.duration() * frame as f32 / (frames - 1).max(1) as f32;
+            let view = camera_path.at(seconds).unwrap();  // The path has keyframes, so it has a view at any time.
+            camera.set_angles(view.yaw, view.pitch, view.roll);
+            camera.orbit(&view.target, view.distance);
+            if let (Some(text), _) = camera.render_frame(headless_model, &headless_options) {
+                println!("{}\n", text);
+            }
+        }
+        exit(0);
+    }
+
+    // In headless mode, print a single frame looking at the model and exit without entering the interactive session.
+    if options.output_format.is_some() {
+        camera.orbit(&center, distance_to_model);  // Back the camera away from the center along its view direction.
+        if let (Some(frame), _) = camera.render_frame(headless_model, &headless_options) {
+            println!("{}", frame);
+        }
+        exit(0);
+    }
+
+    // The interactive session reads keys from the terminal, so without one it would wait for input that never comes.
+    if !io::stdin().is_terminal() {
+        error_close(&"Interactive viewing needs a terminal, use \"--output-format\" to render without one.");
+    }
+
+    // Enable raw terminal mode (no line buffering, etc.), clear the terminal and hide the cursor for the interactive session.
+    TERMINAL_TAKEN.store(true, sync::atomic::Ordering::Relaxed);
+    terminal::enable_raw_mode().unwrap();
+    execute!(
+        io::stdout(),
+        cursor::Hide,  // Hide the cursor.
+        terminal::Clear(terminal::ClearType::All),  // Start from an empty terminal.
+    ).unwrap();
+    if !options.no_mouse {
+        execute!(
+            io::stdout(),
+            event::EnableMouseCapture,  // Enable mouse tracking.
+        ).unwrap();
+        MOUSE_CAPTURED.store(true, sync::atomic::Ordering::Relaxed);
+    }
 
     // Set initial rendering modes.
-    let mut points_mode = false;  // Whether to render points (vertices) or edges.
-    let mut braile_mode = true;  // Whether to render in Braille (or block mode).
+    let mut display_mode = three::DisplayMode::Edges;  // Whether to render edges, points (vertices), or both.
+    let mut pixel_mode = options.pixel_mode.unwrap_or_else(screen::PixelMode::detect);  // Which kind of pixels to render with.
     let mut pan_mode = false;  // Whether to pan the camera.
+    let mut pick_mode = false;  // Whether clicking the model picks a new point to orbit around.
+    let mut light_mode = false;  // Whether dragging the mouse orbits the lights instead of the camera.
+    let mut probe_mode = options.probe;  // Whether to show the coordinates of the vertex under the mouse.
+    let mut probed_vertex: Option<three::Point> = None;  // Vertex under the mouse when it last moved, if any.
+    let mut path_saved = true;  // Whether the camera path's file has the path's latest keyframes.
+    let mut orbit_target = center;  // Point the orbit center is moving towards.
+    let mut selected_group: Option<usize> = None;  // Group of the model that framing zooms in on, or None for the whole model.
+    let mut rotate_speed = options.rotate_speed;  // How fast dragging rotates the view, relative to the default.
+    let mut pan_speed = options.pan_speed;  // How fast dragging pans the view, relative to the default.
+    let mut dither_mode = false;  // Whether to fill faces with dithered flat shading.
+    let mut fill_mode = false;  // Whether to draw depth-tested filled faces instead of edges and points.
+    let mut manifold_mode = false;  // Whether to color edges by the number of faces sharing them.
+    let mut bounding_box_mode = false;  // Whether to draw the model's bounding box.
+    let mut occlusion_mode = false;  // Whether points hide the points behind them.
+    let mut explode_factor = options.explode;  // How far apart the model's groups are pushed.
+    let mut camera_overlay = false;  // Whether to show the camera angles and distance overlay.
+    let mut fog_legend = false;  // Whether to show the fog legend.
+    let mut help_overlay = false;  // Whether to show the controls over the render.
+    let mut sidebar = false;  // Whether to show the sidebar listing the groups and materials.
+    let mut sidebar_selection: usize = 0;  // Entry selected in the sidebar, counting the groups and then the materials.
+    let mut hidden_groups = Vec::<usize>::new();  // Groups whose edges and faces are hidden.
+    let mut hidden_materials = Vec::<usize>::new();  // Materials whose edges and faces are hidden.
+    let mut shown = None::<model::Model>;  // The model with its hidden parts left out and its groups exploded, or None if it's shown as it is.
+    let mut shown_for = None;  // What was hidden and how far the model was exploded for `shown`, or None once the model has changed since.
+
+    // Target durations of a frame while the view is changing and while it is idle, which is never faster than changing.
+    let frame_duration = Duration::from_secs_f32(1. / options.max_fps);
+    let idle_frame_duration = Duration::from_secs_f32(1. / options.max_fps_idle.min(options.max_fps));
 
     // Initialize event tracking (mouse movements, clicks, etc.).
     let mut mouse_speed: (f32, f32) = (0., 0.);
@@ -141,9 +908,10 @@ fn main() {
         let mut start_mouse_position = last_mouse_position;
 
         // Process events from the event queue.
-        let mut event_count = 0;
+        let mut interacting = false;  // Whether any input arrived this frame.
         while event::poll(Duration::from_secs(0)).unwrap() {
             if let Ok(event) = event::read() {
+                interacting = true;
                 match event {
                     event::Event::Key(key_event) => {
                         let is_ctrl_c = key_event.modifiers == event::KeyModifiers::CONTROL
@@ -152,11 +920,250 @@ fn main() {
                         // Exit the program if Ctrl+C is pressed.
                         if is_ctrl_c { graceful_close() }
 
-                        // Toggle points or edges rendering.
-                        if key_event.code == event::KeyCode::Char('p') { points_mode = !points_mode }
+                        // Cycle between edges, points, and both.
+                        if key_event.code == event::KeyCode::Char('p') { display_mode = display_mode.next() }
+
+                        // Toggle hiding points behind nearer ones.
+                        if key_event.code == event::KeyCode::Char('o') { occlusion_mode = !occlusion_mode }
+
+                        // Cycle through the kinds of pixels to render with.
+                        if key_event.code == event::KeyCode::Char('b') { pixel_mode = pixel_mode.next() }
+
+                        // Toggle dithered shading of faces.
+                        if key_event.code == event::KeyCode::Char('d') {
+                            dither_mode = !dither_mode;
+                            // Shading needs face normals, which are computed once the first time it is turned on.
+                            if dither_mode && input_model.face_normals.is_empty() { input_model.cache_normals(); shown_for = None }
+                        }
+
+                        // Toggle culling back faces, which needs the triangles on each side of the edges to cull the edges too.
+                        if key_event.code == event::KeyCode::Char('h') {
+                            camera.cull_backfaces = !camera.cull_backfaces;
+                            if camera.cull_backfaces && input_model.edge_triangles.is_empty() { input_model.cache_edge_triangles(); shown_for = None }
+                        }
+
+                        // Toggle drawing filled faces, which are shaded the same way and so need the normals too.
+                        if key_event.code == event::KeyCode::Char('F') {
+                            fill_mode = !fill_mode;
+                            if fill_mode && input_model.face_normals.is_empty() { input_model.cache_normals(); shown_for = None }
+                        }
+
+                        // Pause or resume the sequence, and step through it a frame at a time.
+                        if let Some(sequence) = sequence.as_mut() {
+                            if key_event.code == event::KeyCode::Char(' ') { sequence.paused = !sequence.paused }
+                            if key_event.code == event::KeyCode::Char('<') { sequence.step(-1) }
+                            if key_event.code == event::KeyCode::Char('>') { sequence.step(1) }
+                        }
+
+                        // Toggle mirroring the model, on the axis given by "--flip" or on the x axis otherwise.
+                        if key_event.code == event::KeyCode::Char('f') {
+                            let axis = flip_axis.unwrap_or(options.flip.unwrap_or(0));
+                            input_model.flip(axis);
+                            shown_for = None;
+                            flip_axis = match flip_axis {
+                                Some(_) => None,
+                                None => Some(axis),
+                            };
+                        }
+
+                        // Toggle the seeded debug colors of faces.
+                        if key_event.code == event::KeyCode::Char('r') {
+                            camera.face_color_seed = match camera.face_color_seed {
+                                Some(_) => None,
+                                None => Some(options.seed),
+                            };
+                        }
+
+                        // Toggle dragging the lights instead of the camera, fixing the default lights in place so they can be moved.
+                        if key_event.code == event::KeyCode::Char('a') {
+                            light_mode = !light_mode;
+                            if light_mode { camera.fix_lights() }
+                        }
+
+                        // Rotate the lights around the model.
+                        if key_event.code == event::KeyCode::Char('l') { camera.rotate_light(LIGHT_ROTATION_STEP) }
+                        if key_event.code == event::KeyCode::Char('L') { camera.rotate_light(-LIGHT_ROTATION_STEP) }
+
+                        // Toggle highlighting of boundary and non-manifold edges.
+                        if key_event.code == event::KeyCode::Char('m') { manifold_mode = !manifold_mode }
+
+                        // Toggle the bounding box.
+                        if key_event.code == event::KeyCode::Char('x') { bounding_box_mode = !bounding_box_mode }
+
+                        // Explode the groups further apart, or bring them back together until the model is assembled.
+                        if key_event.code == event::KeyCode::Char('e') { explode_factor += EXPLODE_STEP }
+                        if key_event.code == event::KeyCode::Char('E') { explode_factor = (explode_factor - EXPLODE_STEP).max(0.) }
+
+                        // Zoom in or out by a fraction of the model's size, so that every model zooms at the same pace,
+                        // stopping just short of the viewport so the camera can't pass through the center of the model.
+                        let zoom_step = (diagonal / 2.).max(VIEWPORT_DISTANCE) * ZOOM_STEP;
+                        match key_event.code {
+                            event::KeyCode::Char('+') | event::KeyCode::Char('=') => {
+                                distance_to_model = (distance_to_model - zoom_step).max(VIEWPORT_DISTANCE * MINIMUM_DISTANCE_MULTIPLIER);
+                            }
+                            event::KeyCode::Char('-') => distance_to_model += zoom_step,
+                            _ => {}
+                        }
+
+                        // Change the field of view, moving the camera to keep the model's size unless the coupling is disabled.
+                        let fov_step = match key_event.code {
+                            event::KeyCode::Char('v') => -FOV_STEP,
+                            event::KeyCode::Char('V') => FOV_STEP,
+                            _ => 0.,
+                        };
+                        if fov_step != 0. {
+                            let fov = (camera.viewport_fov + fov_step).clamp(FOV_LIMITS.0, FOV_LIMITS.1);
+                            if options.fov_coupling {
+                                distance_to_model = distance_for_fov(distance_to_model, diagonal / 2., camera.viewport_fov, fov);
+                            }
+                            camera.viewport_fov = fov;
+                        }
+
+                        // Toggle picking the point to orbit around, or go back to orbiting around the model's center.
+                        if key_event.code == event::KeyCode::Char('i') { pick_mode = !pick_mode }
+                        if key_event.code == event::KeyCode::Char('I') { orbit_target = orbit_home }
+
+                        // Toggle showing the coordinates of the vertex under the mouse.
+                        if key_event.code == event::KeyCode::Char('P') {
+                            probe_mode = !probe_mode;
+                            probed_vertex = None;
+                        }
+
+                        // Cycle the selected group through the model's groups and back to none.
+                        if key_event.code == event::KeyCode::Tab {
+                            selected_group = match selected_group {
+                                None if !input_model.groups.is_empty() => Some(0),
+                                Some(group) if group + 1 < input_model.groups.len() => Some(group + 1),
+                                _ => None,
+                            };
+                        }
 
-                        // Toggle Braille or block mode for rendering.
-                        if key_event.code == event::KeyCode::Char('b') { braile_mode = !braile_mode }
+                        // Frame the selected group, keeping the orientation, or the whole model again if no group is selected.
+                        // Groups are framed the way the whole model is at the start, from the center and diagonal of their bounding box.
+                        if key_event.code == event::KeyCode::Char('z') {
+                            match selected_group.and_then(|group| input_model.group_bounds(group)) {
+                                Some((min, max)) => {
+                                    orbit_target = (min + max) * 0.5;
+                                    distance_to_model = ((max - min).length() * INITIAL_DISTANCE_MULTIPLIER)
+                                        .max(VIEWPORT_DISTANCE * MINIMUM_DISTANCE_MULTIPLIER);
+                                }
+                                None => {
+                                    orbit_target = model_center;
+                                    distance_to_model = options.distance.unwrap_or(diagonal * INITIAL_DISTANCE_MULTIPLIER);
+                                }
+                            }
+                        }
+
+                        // Make dragging rotate or pan faster or slower.
+                        let (slowest, fastest) = SENSITIVITY_LIMITS;
+                        match key_event.code {
+                            event::KeyCode::Char('s') => rotate_speed = (rotate_speed * SENSITIVITY_STEP).min(fastest),
+                            event::KeyCode::Char('S') => rotate_speed = (rotate_speed / SENSITIVITY_STEP).max(slowest),
+                            event::KeyCode::Char('t') => pan_speed = (pan_speed * SENSITIVITY_STEP).min(fastest),
+                            event::KeyCode::Char('T') => pan_speed = (pan_speed / SENSITIVITY_STEP).max(slowest),
+                            _ => {}
+                        }
+
+                        // Toggle the camera angles overlay.
+                        if key_event.code == event::KeyCode::Char('c') { camera_overlay = !camera_overlay }
+
+                        // Show or hide the controls.
+                        match key_event.code {
+                            event::KeyCode::Char('?') => help_overlay = !help_overlay,
+                            event::KeyCode::F(1) => help_overlay = true,
+                            event::KeyCode::Esc => help_overlay = false,
+                            _ => {}
+                        }
+
+                        // Toggle the fog legend.
+                        if key_event.code == event::KeyCode::Char('g') { fog_legend = !fog_legend }
+
+                        // Add the current view to the camera path or clear it, saving it to its file if it has one, or play it back.
+                        match key_event.code {
+                            event::KeyCode::Char('K') | event::KeyCode::Char('C') => {
+                                if key_event.code == event::KeyCode::Char('K') {
+                                    camera_path.keyframes.push(camera_path::Keyframe {
+                                        yaw: view_yaw,
+                                        pitch: view_pitch,
+                                        roll: view_roll,
+                                        distance: distance_to_model,
+                                        target: orbit_target,
+                                    });
+                                } else {
+                                    camera_path.keyframes.clear();
+                                }
+                                if let Some(path) = &options.camera_path {
+                                    path_saved = camera_path.save(path).is_ok();
+                                }
+                            }
+                            event::KeyCode::Char('R') => camera_path.toggle_playback(),
+                            _ => {}
+                        }
+
+                        // Toggle the sidebar, which the render makes room for, and select and hide or show its entries while it is open.
+                        if key_event.code == event::KeyCode::Char('w') {
+                            sidebar = !sidebar;
+                            camera.screen.reserved_columns = if sidebar { SIDEBAR_WIDTH } else { 0 };
+                            execute!(io::stdout(), terminal::Clear(terminal::ClearType::All)).unwrap();
+                        }
+                        if sidebar {
+                            let entries = input_model.groups.len() + input_model.materials.len();
+                            match key_event.code {
+                                event::KeyCode::Up => sidebar_selection = sidebar_selection.saturating_sub(1),
+                                event::KeyCode::Down => sidebar_selection = (sidebar_selection + 1).min(entries.saturating_sub(1)),
+                                event::KeyCode::Enter if sidebar_selection < entries => {
+                                    let (hidden, index) = match sidebar_selection.checked_sub(input_model.groups.len()) {
+                                        None => (&mut hidden_groups, sidebar_selection),
+                                        Some(material) => (&mut hidden_materials, material),
+                                    };
+                                    match hidden.iter().position(|&hidden| hidden == index) {
+                                        Some(position) => { hidden.remove(position); }
+                                        None => hidden.push(index),
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        // Toggle inverted rendering for light terminals.
+                        if key_event.code == event::KeyCode::Char('n') { camera.screen.inverted = !camera.screen.inverted }
+
+                        // Make lines thinner or thicker.
+                        if key_event.code == event::KeyCode::Char('[') { camera.line_width = (camera.line_width - 1).max(1) }
+                        if key_event.code == event::KeyCode::Char(']') { camera.line_width = (camera.line_width + 1).min(MAX_LINE_WIDTH) }
+
+                        // Cycle the clip plane between the axes and off, and move it along its normal.
+                        if key_event.code == event::KeyCode::Char('k') {
+                            clip_axis = match clip_axis {
+                                None => Some(0),
+                                Some(axis) if axis < 2 => Some(axis + 1),
+                                Some(_) => None,
+                            };
+                        }
+                        if key_event.code == event::KeyCode::Char(',') { clip_offset -= diagonal * CLIP_PLANE_STEP }
+                        if key_event.code == event::KeyCode::Char('.') { clip_offset += diagonal * CLIP_PLANE_STEP }
+
+                        // Rotate by a fixed step with the arrow keys, using a finer step while shift is held,
+                        // or pan by a step relative to the size of the model while control is held.
+                        let step = if key_event.modifiers.contains(event::KeyModifiers::SHIFT) {
+                            FINE_ROTATION_STEP
+                        } else {
+                            COARSE_ROTATION_STEP
+                        };
+                        let pan_step = diagonal / 2. * options.pan_step;
+                        match (key_event.code, key_event.modifiers.contains(event::KeyModifiers::CONTROL)) {
+                            // While the sidebar is open, the up and down arrows select its entries instead of rotating.
+                            (event::KeyCode::Up | event::KeyCode::Down, false) if sidebar => {}
+                            (event::KeyCode::Left, true) => pan_view(&mut camera, &mut center, &mut orbit_target, -pan_step, 0.),
+                            (event::KeyCode::Right, true) => pan_view(&mut camera, &mut center, &mut orbit_target, pan_step, 0.),
+                            (event::KeyCode::Up, true) => pan_view(&mut camera, &mut center, &mut orbit_target, 0., pan_step),
+                            (event::KeyCode::Down, true) => pan_view(&mut camera, &mut center, &mut orbit_target, 0., -pan_step),
+                            (event::KeyCode::Left, false) => view_yaw -= step,
+                            (event::KeyCode::Right, false) => view_yaw += step,
+                            (event::KeyCode::Up, false) => view_pitch += step,
+                            (event::KeyCode::Down, false) => view_pitch -= step,
+                            _ => {}
+                        }
                     }
 
                     // Handle mouse events for navigation.
@@ -170,35 +1177,234 @@ fn main() {
                                 last_mouse_position.x = x as i32;
                                 last_mouse_position.y = y as i32;
                                 start_mouse_position = last_mouse_position;
-                                event_count += 1;
+
+                                // Pick the point under the middle of the clicked cell as the new orbit center.
+                                if pick_mode {
+                                    let pixel = cell_center_pixel(pixel_mode, x, y);
+                                    if let Some(point) = camera.pick(&input_model, &pixel) { orbit_target = point }
+                                }
+                            }
+
+                            // Find the vertex under the middle of the cell the mouse moved to, when probing.
+                            event::MouseEventKind::Moved if probe_mode => {
+                                probed_vertex = camera.nearest_vertex(&input_model, &cell_center_pixel(pixel_mode, x, y));
                             }
 
                             // If the mouse is dragged, calculate movement speed.
+                            // Unless panning, the lights are orbited instead of the camera in light mode, keeping the model still.
+                            event::MouseEventKind::Drag(_) if light_mode && mouse_event.modifiers != event::KeyModifiers::SHIFT => {
+                                let delta_x = x as f32 - last_mouse_position.x as f32;
+                                let delta_y = last_mouse_position.y as f32 - y as f32;
+                                camera.orbit_light(delta_x * LIGHT_DRAG_STEP * rotate_speed, delta_y * LIGHT_DRAG_STEP * rotate_speed);
+                                last_mouse_position = screen::Point::new(x as i32, y as i32);
+                            }
+
                             event::MouseEventKind::Drag(_) => {
                                 pan_mode = mouse_event.modifiers == event::KeyModifiers::SHIFT;
                                 let delta_x = x as f32 - start_mouse_position.x as f32;
                                 let delta_y = start_mouse_position.y as f32 - y as f32;
-                                mouse_speed.0 = delta_x / camera.screen.width as f32 * MOUSE_SPEED_MULTIPLIER;
-                                mouse_speed.1 = delta_y / camera.screen.width as f32 * MOUSE_SPEED_MULTIPLIER;
+                                // Panning and rotating are comfortable at different speeds, so each has its own.
+                                // The mouse moves by terminal cells, so the drag is measured against the terminal's width in cells.
+                                let sensitivity = if pan_mode { PAN_MULTIPLIER * pan_speed } else { MOUSE_SPEED_MULTIPLIER * rotate_speed };
+                                let columns = terminal::size().map_or(1, |(columns, _)| columns.max(1)) as f32;
+                                mouse_speed.0 = delta_x / columns * sensitivity;
+                                mouse_speed.1 = delta_y / columns * sensitivity;
                                 last_mouse_position = screen::Point::new(x as i32, y as i32);
                             }
+
+                            // Scrolling zooms by a fraction of the distance, so each notch feels the same up close and far away,
+                            // stopping just short of the viewport like the zoom keys.
+                            event::MouseEventKind::ScrollUp => {
+                                distance_to_model = (distance_to_model * (1. - SCROLL_MULTIPLIER)).max(VIEWPORT_DISTANCE * MINIMUM_DISTANCE_MULTIPLIER);
+                            }
+                            event::MouseEventKind::ScrollDown => distance_to_model *= 1. + SCROLL_MULTIPLIER,
                             _ => {}
                         }
                     }
+
+                    // Blank the terminal after a resize, as it may have rewrapped or kept parts of the old frame.
+                    // The frame is fitted to the terminal's new size when it is drawn, which keeps the model centered.
+                    event::Event::Resize(_, _) => {
+                        execute!(io::stdout(), terminal::Clear(terminal::ClearType::All)).unwrap();
+                    }
                     _ => {}
                 }
             }
         }
 
+        // Reload the model once its file has settled after a change, keeping the current view.
+        // A file caught halfway through being saved fails to load, so the last good model is kept and the load retried.
+        if let Some(watcher) = watcher.as_mut() {
+            if watcher.poll() {
+                match load_model(&watcher.path, !options.no_dedup, options.local_origin) {
+                    Ok(mut model) => {
+                        prepare_model(&mut model, &options, flip_axis);
+                        if !input_model.face_normals.is_empty() { model.cache_normals() }  // Keep normals available for shading.
+                        if !input_model.edge_triangles.is_empty() { model.cache_edge_triangles() }  // Keep culling the edges.
+                        input_model = model;
+                        shown_for = None;
+                    }
+                    Err(_) => watcher.retry(),
+                }
+            }
+        }
+
+        // Show the next frame of the sequence when it is due, keeping the camera framed on the first frame so it doesn't jump.
+        // Frames that fail to load are skipped over, leaving the last good one on screen.
+        if let Some(sequence) = sequence.as_mut() {
+            if let Some(path) = sequence.poll() {
+                if let Ok(mut model) = load_model(path, !options.no_dedup, options.local_origin) {
+                    prepare_model(&mut model, &options, flip_axis);
+                    if !input_model.face_normals.is_empty() { model.cache_normals() }  // Keep normals available for shading.
+                    if !input_model.edge_triangles.is_empty() { model.cache_edge_triangles() }  // Keep culling the edges.
+                    input_model = model;
+                    shown_for = None;
+                }
+            }
+        }
+
+        // Draw a decimated model while the view is being moved, and the full model again on the first idle frame.
+        camera.edge_budget = if interacting {
+            let budget = options.interactive_max_edges.unwrap_or(INTERACTIVE_EDGE_BUDGET);
+            Some(options.max_edges.map_or(budget, |max_edges| max_edges.min(budget)))
+        } else {
+            options.max_edges
+        };
+
+        // Fly along the camera path while it plays, taking over the view from the controls.
+        if let Some(view) = camera_path.poll() {
+            view_yaw = view.yaw;
+            view_pitch = view.pitch;
+            view_roll = view.roll;
+            distance_to_model = view.distance;
+            center = view.target;
+            orbit_target = view.target;
+        }
+
+        // Ease the orbit center towards the picked point, so that the view glides over instead of jumping.
+        let easing = (orbit_target - center).length() > diagonal * EASING_SETTLED;
+        center = center + (orbit_target - center) * PICK_EASING;
+
+        // Move the section view's clip plane to its current axis and offset, which stays relative to the model's center.
+        camera.clip_plane = clip_plane(clip_axis, &model_center, clip_offset);
+
+        // Rotate by the mouse drag since the last frame unless panning, spinning around the vertical axis when dragging sideways
+        // and tilting when dragging up or down, the same ways as the arrow keys. The drag is used up once applied.
+        if !pan_mode {
+            view_yaw += mouse_speed.0;
+            view_pitch += mouse_speed.1;
+            mouse_speed = (0., 0.);
+        }
+
+        // Apply the accumulated view angles, keeping them in sync with the wrapped and clamped camera angles.
+        // The pitch limit stops just short of straight up or down, where the camera would flip over.
+        camera.set_angles(view_yaw, view_pitch, view_roll);
+        view_yaw = camera.yaw;
+        view_pitch = camera.pitch;
+
         // Handle camera movement based on mouse input (rotation and panning).
+        // Panning moves the camera and its orbit center against the drag, so the model follows the mouse. Scaling by the distance
+        // keeps the pace the same at any zoom. The drag is used up once applied, so the view stops when the mouse does.
         if pan_mode {
-            // Implement pan logic here.
+            pan_view(&mut camera, &mut center, &mut orbit_target, -mouse_speed.0 * distance_to_model, -mouse_speed.1 * distance_to_model);
+            mouse_speed = (0., 0.);
+        }
+
+        // Place the camera on the sphere of the current distance around the orbit center, facing it along the view angles.
+        camera.orbit(&center, distance_to_model);
+
+        // Stamp the overlays onto the screen's overlay layer, to be drawn over the frame along with it, replacing the last frame's overlays.
+        // The camera overlay shows the statistics of the last frame drawn, as this one hasn't been drawn yet.
+        camera.screen.clear_overlay();
+        if camera_overlay {
+            let text = format!(
+                "{} {}",
+                camera_overlay_text(view_yaw, view_pitch, view_roll, distance_to_model, camera.viewport_fov, &camera.lights, (rotate_speed, pan_speed)),
+                render_stats_text(&camera.stats, input_model.removed_zero_length_edges),
+            );
+            camera.screen.blit_text((0, 0), &[&text], screen::OverlayStyle::default());
+        }
+
+        // Stamp the fog legend onto the top-right corner, clear of the status line.
+        if let Some(text) = camera.fog.filter(|_| fog_legend).map(|fog| fog_legend_text(fog, camera.screen.inverted)) {
+            let column = terminal::size().map_or(0, |(columns, _)| columns.saturating_sub(text.chars().count() as u16));
+            camera.screen.blit_text((column, 0), &[&text], screen::OverlayStyle::default());
+        }
+
+        // Stamp the status line onto the bottom row, with the current frame of a sequence in place of the model's name.
+        let name = match &sequence {
+            Some(sequence) => format!("{} ({}/{})", file_name(&sequence.paths[sequence.index]), sequence.index + 1, sequence.paths.len()),
+            None => model_name.clone(),
+        };
+        let mut active_modes = Vec::<&str>::new();
+        if dither_mode { active_modes.push("shading") }
+        if fill_mode { active_modes.push("filled faces") }
+        if camera.cull_backfaces { active_modes.push("back faces culled") }
+        if manifold_mode { active_modes.push("manifold edges") }
+        if bounding_box_mode { active_modes.push("bounding box") }
+        if occlusion_mode && display_mode == three::DisplayMode::Points { active_modes.push("occluded points") }
+        if explode_factor > 0. { active_modes.push("exploded") }
+        if camera.face_color_seed.is_some() { active_modes.push("face colors") }
+        if clip_axis.is_some() { active_modes.push("section view") }
+        if pick_mode { active_modes.push("picking") }
+        if light_mode { active_modes.push("moving lights") }
+        let probe_text = match probed_vertex {
+            Some(vertex) => format!("vertex {:.4}, {:.4}, {:.4}", vertex.x, vertex.y, vertex.z),
+            None => String::from("no vertex under the mouse"),
+        };
+        if probe_mode { active_modes.push(&probe_text) }
+        let selected_name = selected_group.and_then(|group| input_model.groups.get(group)).map(|name| format!("group {}", name));
+        if let Some(name) = &selected_name { active_modes.push(name) }
+        if flip_axis.is_some() { active_modes.push("mirrored") }
+        if camera.screen.inverted { active_modes.push("inverted") }
+        if fog_legend && camera.fog.is_none() { active_modes.push("fog legend (no fog)") }
+        if sequence.as_ref().is_some_and(|sequence| sequence.paused) { active_modes.push("paused") }
+        let path_text = format!(
+            "camera path of {} keyframe(s){}",
+            camera_path.keyframes.len(),
+            if camera_path.playing() { ", playing" } else { "" },
+        );
+        if !camera_path.keyframes.is_empty() { active_modes.push(&path_text) }
+        if !path_saved { active_modes.push("camera path not saved") }
+        camera.screen.blit_status_line(&status_line_text(&name, pixel_mode, display_mode, &active_modes));
+
+        // Stamp the sidebar onto the columns the render leaves for it.
+        if sidebar {
+            draw_sidebar(&mut camera.screen, &input_model, (&hidden_groups, &hidden_materials), sidebar_selection);
+        }
+
+        // Stamp the help overlay on top of everything else.
+        if help_overlay {
+            draw_help_overlay(&mut camera.screen, &active_modes);
+        }
+
+        // Draw the frame with the current modes and the overlays over it, fitting the screen to the terminal for the chosen kind of pixels.
+        let mut render_options = three::RenderOptions::new();
+        render_options.display_mode = display_mode;
+        render_options.pixel_mode = pixel_mode;
+        render_options.shading = dither_mode;
+        render_options.filled_faces = fill_mode;
+        render_options.manifold_edges = manifold_mode;
+        render_options.bounding_box = bounding_box_mode;
+        render_options.light_markers = light_mode;
+        render_options.occlude_points = occlusion_mode;
+
+        // Hide and explode the model again only once that or the model itself has changed, rather than copying it every frame.
+        let showing = (hidden_groups.clone(), hidden_materials.clone(), explode_factor);
+        if shown_for.as_ref() != Some(&showing) {
+            shown = shown_model(&input_model, (&hidden_groups, &hidden_materials), explode_factor);
+            shown_for = Some(showing);
         }
+        camera.render_frame(shown.as_ref().unwrap_or(&input_model), &render_options);
 
-        // Wait for the next frame to maintain the target FPS.
+        // Wait for the next frame to maintain the target FPS, which is lower while nothing is changing or animating.
+        // An idle wait ends as soon as input arrives, so that the frame reacting to it isn't held back.
+        let animating = interacting || easing || camera_path.playing() || sequence.as_ref().is_some_and(|sequence| sequence.playing());
         let elapsed = start.elapsed();
-        if elapsed < TARGET_DURATION_PER_FRAME {
-            std::thread::sleep(TARGET_DURATION_PER_FRAME - elapsed);
+        if animating && elapsed < frame_duration {
+            std::thread::sleep(frame_duration - elapsed);
+        } else if !animating && elapsed < idle_frame_duration {
+            event::poll(idle_frame_duration - elapsed).unwrap();
         }
     }
 }
@@ -1,6 +1,66 @@
 use crate::three;
 use std::*;
 
+// Largest number of subdivisions allowed for an icosphere, which already has over 80,000 triangles.
+const MAX_ICOSPHERE_SUBDIVISIONS: u32 = 6;
+
+// Largest number of subdivision iterations applied to a loaded model, each of which multiplies its triangle count by four.
+const MAX_SUBDIVISIONS: u32 = 4;
+
+// Number of triangles above which subdividing a model warns that rendering it may be slow.
+const LARGE_TRIANGLE_COUNT: usize = 1_000_000;
+
+// Name of the group that .obj geometry outside of any named group belongs to, as in the .obj format itself.
+const DEFAULT_GROUP_NAME: &str = "default";
+
+// First two bytes of gzip-compressed data, and whether this build can decompress it.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+pub const GZIP_AVAILABLE: bool = cfg!(feature = "gzip");
+
+// A file format that models can be loaded from, recognized by its file extensions.
+pub struct Format {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    // Cargo feature the format needs to be built with, if any, and whether this build has it.
+    pub feature: Option<&'static str>,
+    pub available: bool,
+}
+
+// Every format models can be loaded from, whether or not this build supports it. Files with unknown extensions are loaded as the first.
+pub const FORMATS: [Format; 2] = [
+    Format { name: "Wavefront OBJ", extensions: &["obj"], feature: None, available: true },
+    Format { name: "3D Manufacturing Format", extensions: &["3mf"], feature: Some("threemf"), available: cfg!(feature = "threemf") },
+];
+
+// Returns the format a file's extension belongs to, ignoring case, or None if the extension is unknown.
+// Compressed files are recognized by the extension before ".gz", as in "model.obj.gz".
+pub fn format_of(path: &str) -> Option<&'static Format> {
+    let mut path = path::Path::new(path);
+    if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gz")) {
+        path = path::Path::new(path.file_stem()?);
+    }
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    FORMATS.iter().find(|format| format.extensions.contains(&extension.as_str()))
+}
+
+// Wraps a reader so that gzip-compressed data is decompressed as it is read, recognizing it by its magic bytes
+// rather than by a ".gz" extension, so that compressed data loads however it is named or wherever it comes from.
+// Anything else is read as it is.
+fn decompressed<'a, R: io::Read + 'a>(reader: R) -> Result<Box<dyn io::Read + 'a>, Box<dyn error::Error>> {
+    use io::BufRead;
+
+    let mut reader = io::BufReader::new(reader);
+    if !reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        return Ok(Box::new(reader));
+    }
+
+    #[cfg(feature = "gzip")]
+    return Ok(Box::new(flate2::read::MultiGzDecoder::new(reader)));
+
+    #[cfg(not(feature = "gzip"))]
+    Err(Box::from("Loading gzip-compressed files requires building with the \"gzip\" feature."))
+}
+
 // Error struct for parsing .obj file failures.
 #[derive(Debug)]
 struct ObjParseError;
@@ -27,15 +87,100 @@ impl error::Error for ObjParseError {
     }
 }
 
+// A material from a .mtl library, referred to by name from .obj files.
+#[derive(Clone)]
+pub struct Material {
+    pub name: String,
+    // Diffuse color ("Kd") as red, green and blue components from 0 to 1, or None if the material has no color.
+    pub color: Option<[f32; 3]>,
+}
+
+// Counts of the common problems found in a mesh by validation, which are all zero (and one component) for a clean closed mesh.
+#[derive(Copy, Clone)]
+pub struct Validation {
+    // Triangle sides shared by more than two triangles, and by only one, which are the edges of holes.
+    pub non_manifold_edges: usize,
+    pub boundary_edges: usize,
+    // Points at the exact coordinates of an earlier point.
+    pub duplicate_vertices: usize,
+    // Triangles with no area, either because two corners are the same point or because the corners are in a line.
+    pub degenerate_triangles: usize,
+    // Number of separate pieces the model is made of.
+    pub components: usize,
+    // Triangles wound the other way around from their neighbors, and pieces of neighboring triangles that can't all agree on
+    // a winding, such as a Möbius strip.
+    pub flipped_triangles: usize,
+    pub non_orientable_pieces: usize,
+    // Zero-length edges dropped while loading and preparing the model. These are reported rather than counted as a problem,
+    // since none of them are left in the mesh.
+    pub removed_zero_length_edges: usize,
+}
+
+impl Validation {
+    // Returns whether any problem was found.
+    pub fn has_problems(&self) -> bool {
+        self.non_manifold_edges > 0 || self.boundary_edges > 0 || self.duplicate_vertices > 0 || self.degenerate_triangles > 0 || self.components > 1
+            || self.flipped_triangles > 0 || self.non_orientable_pieces > 0
+    }
+}
+
+// Counts of the elements of a model, for showing its size and budgeting how much of it to draw.
+#[derive(Copy, Clone)]
+pub struct Summary {
+    pub points: usize,
+    pub edges: usize,
+    pub triangles: usize,
+    // Zero-length edges dropped from the model, which aren't counted in `edges`.
+    pub removed_zero_length_edges: usize,
+}
+
 // Struct representing a 3D model.
+#[derive(Clone)]
 pub struct Model {
     // List of points (vertices) defined in model space.
     pub points: Vec<three::Point>,
     // List of edges, each represented as a tuple of points (start and end).
     pub edges: Vec<(three::Point, three::Point)>,
+    // List of triangles, each represented as three indices into `points`. Faces with more than three sides are split into fans,
+    // which can be wrong for concave faces; see parse_obj.
+    pub triangles: Vec<[usize; 3]>,
+    // Indices into `points` of the vertices declared as standalone point elements, drawn along with the edges.
+    pub point_indices: Vec<usize>,
+    // Unit normal of each triangle, and the smoothed unit normal at each of its corners, empty until `cache_normals` is called.
+    pub face_normals: Vec<three::Point>,
+    pub vertex_normals: Vec<[three::Point; 3]>,
+    // Normals given in the file ("vn"), and the index into `normals` of the normal given for each corner of each triangle, if any.
+    // The corner normals are either empty or line up with `triangles`, and take the place of the smoothed normals at their corners.
+    pub normals: Vec<three::Point>,
+    pub triangle_normals: Vec<[Option<usize>; 3]>,
+    // Up to two triangles that each edge is a side of, lined up with `edges`, empty until `cache_edge_triangles` is called.
+    pub edge_triangles: Vec<[Option<usize>; 2]>,
+    // Whether the triangles of a closed mesh are wound outwards, as found by `outward_winding`, with None for other meshes.
+    // Like `edge_triangles`, it is only filled when `cache_edge_triangles` is called.
+    pub winding: Option<bool>,
+    // Number of zero-length edges dropped so far, while loading the model and whenever its edges were checked again.
+    pub removed_zero_length_edges: usize,
+    // Non-fatal problems found while loading the model, such as statements that were skipped.
+    pub warnings: Vec<String>,
+    // Materials used by the model, and the index into `materials` of each edge's and triangle's material, if any.
+    // The material indices are either empty or line up with `edges` and `triangles`.
+    pub materials: Vec<Material>,
+    pub edge_materials: Vec<Option<usize>>,
+    pub triangle_materials: Vec<Option<usize>>,
+    // Smoothing group ("s") of each triangle, with None for triangles that are shaded flat.
+    // Either empty, in which case all triangles are smoothed together, or lined up with `triangles`.
+    pub triangle_smoothing_groups: Vec<Option<u32>>,
+    // Names of the groups ("g" and "o") the model is divided into, such as the parts of an assembly,
+    // and the index into `groups` of each edge's and triangle's group, if any. The group indices are either empty or line up with `edges` and `triangles`.
+    pub groups: Vec<String>,
+    pub edge_groups: Vec<Option<usize>>,
+    pub triangle_groups: Vec<Option<usize>>,
 
     // Position of the model in world space (corresponds to the (0, 0, 0) point in model space).
     pub position: three::Point,
+    // Coordinates in the file of the (0, 0, 0) point in model space, which were subtracted from every vertex while loading
+    // to keep far away models precise, or all zeros for models loaded as they are.
+    pub origin: [f64; 3],
 }
 
 #[allow(dead_code)]
@@ -49,7 +194,25 @@ impl Model {
         Model {
             points,
             position,
+            origin: [0.; 3],
             edges,
+            triangles: Vec::new(),
+            point_indices: Vec::new(),
+            face_normals: Vec::new(),
+            vertex_normals: Vec::new(),
+            normals: Vec::new(),
+            triangle_normals: Vec::new(),
+            edge_triangles: Vec::new(),
+            winding: None,
+            removed_zero_length_edges: 0,
+            warnings: Vec::new(),
+            materials: Vec::new(),
+            edge_materials: Vec::new(),
+            triangle_materials: Vec::new(),
+            triangle_smoothing_groups: Vec::new(),
+            groups: Vec::new(),
+            edge_groups: Vec::new(),
+            triangle_groups: Vec::new(),
         }
     }
 
@@ -93,25 +256,427 @@ impl Model {
                 (rear.2, front.2),
                 (rear.3, front.3),
             ],
+            triangles: Vec::new(), // No triangles since the cube has no points to index into.
+            point_indices: Vec::new(),
+            face_normals: Vec::new(),
+            vertex_normals: Vec::new(),
+            normals: Vec::new(),
+            triangle_normals: Vec::new(),
+            edge_triangles: Vec::new(),
+            winding: None,
+            removed_zero_length_edges: 0,
+            warnings: Vec::new(),
+            materials: Vec::new(),
+            edge_materials: Vec::new(),
+            triangle_materials: Vec::new(),
+            triangle_smoothing_groups: Vec::new(),
+            groups: Vec::new(),
+            edge_groups: Vec::new(),
+            triangle_groups: Vec::new(),
             position,
+            origin: [0.; 3],
+        }
+    }
+
+    // Creates a flat square grid on the XZ plane with a specified side length and number of divisions, centered at a specified position.
+    pub fn new_grid(size: f32, divisions: usize, position: three::Point) -> Model {
+        let divisions = divisions.max(1);
+        let half = size / 2.;
+        let step = size / divisions as f32;
+
+        // Place a point at every intersection of the grid.
+        let mut points = Vec::new();
+        for row in 0..=divisions {
+            for column in 0..=divisions {
+                points.push(three::Point::new(-half + column as f32 * step, 0., -half + row as f32 * step));
+            }
+        }
+
+        // Add one edge running along the X axis and one along the Z axis for each division line.
+        let mut edges = Vec::new();
+        for line in 0..=divisions {
+            let offset = -half + line as f32 * step;
+            edges.push((three::Point::new(-half, 0., offset), three::Point::new(half, 0., offset)));
+            edges.push((three::Point::new(offset, 0., -half), three::Point::new(offset, 0., half)));
+        }
+
+        Model {
+            points,
+            edges,
+            triangles: Vec::new(), // The grid is drawn as lines only.
+            point_indices: Vec::new(),
+            face_normals: Vec::new(),
+            vertex_normals: Vec::new(),
+            normals: Vec::new(),
+            triangle_normals: Vec::new(),
+            edge_triangles: Vec::new(),
+            winding: None,
+            removed_zero_length_edges: 0,
+            warnings: Vec::new(),
+            materials: Vec::new(),
+            edge_materials: Vec::new(),
+            triangle_materials: Vec::new(),
+            triangle_smoothing_groups: Vec::new(),
+            groups: Vec::new(),
+            edge_groups: Vec::new(),
+            triangle_groups: Vec::new(),
+            position,
+            origin: [0.; 3],
+        }
+    }
+
+    // Creates a model from points and outward-facing (counter-clockwise) triangles, with an edge along each side of every triangle.
+    fn from_triangles(points: Vec<three::Point>, triangles: Vec<[usize; 3]>, position: three::Point) -> Model {
+        // Collect each triangle side once, regardless of which triangle it came from.
+        let mut edges = Vec::<(usize, usize)>::new();
+        for triangle in triangles.iter() {
+            for side in 0..3 {
+                let (start, end) = (triangle[side], triangle[(side + 1) % 3]);
+                edges.push((start.min(end), start.max(end)));
+            }
+        }
+        edges.sort();
+        edges.dedup();
+
+        Model {
+            edges: edges.into_iter().map(|(start, end)| (points[start], points[end])).collect(),
+            points,
+            triangles,
+            point_indices: Vec::new(),
+            face_normals: Vec::new(),
+            vertex_normals: Vec::new(),
+            normals: Vec::new(),
+            triangle_normals: Vec::new(),
+            edge_triangles: Vec::new(),
+            winding: None,
+            removed_zero_length_edges: 0,
+            warnings: Vec::new(),
+            materials: Vec::new(),
+            edge_materials: Vec::new(),
+            triangle_materials: Vec::new(),
+            triangle_smoothing_groups: Vec::new(),
+            groups: Vec::new(),
+            edge_groups: Vec::new(),
+            triangle_groups: Vec::new(),
+            position,
+            origin: [0.; 3],
+        }
+    }
+
+    // Scales a point so that it lies at a specified distance from the origin.
+    fn project_to_sphere(point: &three::Point, radius: f32) -> three::Point {
+        point.normalized() * radius
+    }
+
+    // Creates a regular tetrahedron with its vertices at a specified radius, centered at a specified position.
+    pub fn new_tetrahedron(radius: f32, position: three::Point) -> Model {
+        let points = [(1., 1., 1.), (1., -1., -1.), (-1., 1., -1.), (-1., -1., 1.)]
+            .map(|(x, y, z)| Model::project_to_sphere(&three::Point::new(x, y, z), radius))
+            .to_vec();
+        let triangles = vec![[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]];
+        Model::from_triangles(points, triangles, position)
+    }
+
+    // Creates a regular octahedron with its vertices at a specified radius, centered at a specified position.
+    pub fn new_octahedron(radius: f32, position: three::Point) -> Model {
+        let points = vec![
+            three::Point::new(radius, 0., 0.),
+            three::Point::new(-radius, 0., 0.),
+            three::Point::new(0., radius, 0.),
+            three::Point::new(0., -radius, 0.),
+            three::Point::new(0., 0., radius),
+            three::Point::new(0., 0., -radius),
+        ];
+        let triangles = vec![
+            // Upper half, around the top vertex.
+            [0, 2, 4], [4, 2, 1], [1, 2, 5], [5, 2, 0],
+            // Lower half, around the bottom vertex.
+            [0, 4, 3], [4, 1, 3], [1, 5, 3], [5, 0, 3],
+        ];
+        Model::from_triangles(points, triangles, position)
+    }
+
+    // Creates a regular icosahedron with its vertices at a specified radius, centered at a specified position.
+    pub fn new_icosahedron(radius: f32, position: three::Point) -> Model {
+        let (points, triangles) = Model::icosahedron_mesh(radius);
+        Model::from_triangles(points, triangles, position)
+    }
+
+    // Creates a sphere by repeatedly splitting each triangle of an icosahedron into four, centered at a specified position.
+    pub fn new_icosphere(radius: f32, subdivisions: u32, position: three::Point) -> Model {
+        let (mut points, mut triangles) = Model::icosahedron_mesh(radius);
+
+        // Each subdivision multiplies the triangle count by four, so cap it before the mesh gets unreasonably large.
+        for _ in 0..subdivisions.min(MAX_ICOSPHERE_SUBDIVISIONS) {
+            let mut midpoints = collections::HashMap::<(usize, usize), usize>::new();
+            let mut midpoint = |start: usize, end: usize, points: &mut Vec<three::Point>| -> usize {
+                // Share the midpoint of each side between the two triangles that meet there.
+                *midpoints.entry((start.min(end), start.max(end))).or_insert_with(|| {
+                    let (a, b) = (points[start], points[end]);
+                    let middle = three::Point::new((a.x + b.x) / 2., (a.y + b.y) / 2., (a.z + b.z) / 2.);
+                    points.push(Model::project_to_sphere(&middle, radius));
+                    points.len() - 1
+                })
+            };
+
+            let mut subdivided = Vec::with_capacity(triangles.len() * 4);
+            for [a, b, c] in triangles {
+                let (ab, bc, ca) = (midpoint(a, b, &mut points), midpoint(b, c, &mut points), midpoint(c, a, &mut points));
+                subdivided.extend([[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+            }
+            triangles = subdivided;
         }
+
+        Model::from_triangles(points, triangles, position)
     }
 
-    // Creates a model from a .obj file, placing it at a specified position in world space.
-    pub fn new_obj(path: &str, position: three::Point) -> Result<Model, Box<dyn error::Error>> {
-        // Read the contents of the .obj file into a string.
-        let mut code = fs::read_to_string(path)?;
+    // Returns the points and outward-facing triangles of a regular icosahedron with its vertices at a specified radius.
+    fn icosahedron_mesh(radius: f32) -> (Vec<three::Point>, Vec<[usize; 3]>) {
+        let t = (1. + 5f32.sqrt()) / 2.;  // The golden ratio.
+        let points = [
+            (-1., t, 0.), (1., t, 0.), (-1., -t, 0.), (1., -t, 0.),
+            (0., -1., t), (0., 1., t), (0., -1., -t), (0., 1., -t),
+            (t, 0., -1.), (t, 0., 1.), (-t, 0., -1.), (-t, 0., 1.),
+        ].map(|(x, y, z)| Model::project_to_sphere(&three::Point::new(x, y, z), radius)).to_vec();
 
-        // Pre-process the code to handle escaped newlines that continue to the next line.
-        code = code.replace("\\\n", " ");
-        
-        // Vectors to store parsed vertices, lines, and faces.
-        let mut vertices = Vec::<three::Point>::new();
+        let triangles = vec![
+            [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+            [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+            [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+            [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+        ];
+        (points, triangles)
+    }
+
+    // Creates a model from a file, choosing the format from its extension and placing it at a specified position in world space.
+    // Whether duplicate edges are removed only matters for .obj files, see new_obj. With local_origin, the center of the bounding box
+    // becomes the origin of model space, which .obj files do in double precision while loading and other formats afterwards.
+    pub fn new_from_extension(path: &str, position: three::Point, dedup: bool, local_origin: bool) -> Result<Model, Box<dyn error::Error>> {
+        // Anything with an unknown extension is treated as a .obj file.
+        let format = format_of(path).unwrap_or(&FORMATS[0]);
+        if let (false, Some(feature)) = (format.available, format.feature) {
+            return Err(Box::from(format!(
+                "Loading .{} files requires building with the \"{}\" feature.",
+                format.extensions[0], feature
+            )));
+        }
+
+        match format.extensions[0] {
+            #[cfg(feature = "threemf")]
+            "3mf" => {
+                let mut model = Model::new_3mf(path, position)?;
+                if local_origin { model.move_origin_to_center() }
+                Ok(model)
+            }
+            _ => Model::new_obj(path, position, dedup, local_origin),
+        }
+    }
+
+    // Creates a model from a .3mf file, placing it at a specified position in world space.
+    // Only mesh objects are supported, and each build item is placed using its transform.
+    #[cfg(feature = "threemf")]
+    pub fn new_3mf(path: &str, position: three::Point) -> Result<Model, Box<dyn error::Error>> {
+        use io::Read;
+
+        // A .3mf file is a zip archive, with the model itself stored as XML.
+        let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+        let mut xml = String::new();
+        archive.by_name("3D/3dmodel.model")?.read_to_string(&mut xml)?;
+        let document = roxmltree::Document::parse(&xml)?;
+
+        // Parses a numeric attribute of an element.
+        let number = |node: &roxmltree::Node, name: &str| -> Result<f32, Box<dyn error::Error>> {
+            Ok(node.attribute(name).ok_or(ObjParseError::new())?.parse::<f32>()?)
+        };
+        let index = |node: &roxmltree::Node, name: &str| -> Result<usize, Box<dyn error::Error>> {
+            Ok(node.attribute(name).ok_or(ObjParseError::new())?.parse::<usize>()?)
+        };
+
+        // Collect the vertices and triangles of each mesh object by its id.
+        let mut objects = collections::HashMap::<String, (Vec<three::Point>, Vec<[usize; 3]>)>::new();
+        for object in document.descendants().filter(|node| node.tag_name().name() == "object") {
+            let mut vertices = Vec::new();
+            let mut triangles = Vec::new();
+            for node in object.descendants() {
+                match node.tag_name().name() {
+                    "vertex" => vertices.push(three::Point::new(number(&node, "x")?, number(&node, "y")?, number(&node, "z")?)),
+                    "triangle" => triangles.push([index(&node, "v1")?, index(&node, "v2")?, index(&node, "v3")?]),
+                    _ => {}
+                }
+            }
+            if triangles.iter().flatten().any(|&index| index >= vertices.len()) {
+                return Err(Box::from(ObjParseError::new()));
+            }
+            let id = String::from(object.attribute("id").unwrap_or_default());
+            objects.insert(id, (vertices, triangles));
+        }
+
+        // Place each build item's object into a single mesh, applying the item's 3x4 affine transform if it has one.
+        let mut points = Vec::new();
+        let mut triangles = Vec::new();
+        for item in document.descendants().filter(|node| node.tag_name().name() == "item") {
+            let (vertices, object_triangles) = match objects.get(item.attribute("objectid").unwrap_or_default()) {
+                Some(object) => object,
+                None => continue,
+            };
+
+            let transform: Vec<f32> = match item.attribute("transform") {
+                Some(text) => text.split_whitespace().map(str::parse::<f32>).collect::<Result<_, _>>()?,
+                None => vec![1., 0., 0., 0., 1., 0., 0., 0., 1., 0., 0., 0.],
+            };
+            if transform.len() != 12 {
+                return Err(Box::from(ObjParseError::new()));
+            }
+
+            let offset = points.len();
+            points.extend(vertices.iter().map(|vertex| three::Point::new(
+                vertex.x * transform[0] + vertex.y * transform[3] + vertex.z * transform[6] + transform[9],
+                vertex.x * transform[1] + vertex.y * transform[4] + vertex.z * transform[7] + transform[10],
+                vertex.x * transform[2] + vertex.y * transform[5] + vertex.z * transform[8] + transform[11],
+            )));
+            triangles.extend(object_triangles.iter().map(|triangle| triangle.map(|index| index + offset)));
+        }
+
+        Ok(Model::from_triangles(points, triangles, position))
+    }
+
+    // Creates a model from a .obj file, which may be gzip-compressed, placing it at a specified position in world space.
+    // Material libraries referenced by the file are looked up relative to the file's directory.
+    // Files without any geometry, such as empty or comment-only ones, are reported as errors rather than shown as a blank screen.
+    // Edges are sorted and the duplicates shared by neighboring faces removed, unless dedup is false, which keeps them in file order
+    // with every duplicate, as needed to round-trip a file faithfully. That draws most edges of a mesh twice and holds them twice in memory.
+    pub fn new_obj(path: &str, position: three::Point, dedup: bool, local_origin: bool) -> Result<Model, Box<dyn error::Error>> {
+        let file = decompressed(fs::File::open(path)?)?;
+        let model = Model::parse_obj(file, path::Path::new(path).parent(), position, dedup, local_origin)?;
+        model.require_geometry(path)
+    }
+
+    // Creates a model from a .obj file served over HTTP(S), placing it at a specified position in world space.
+    #[cfg(feature = "net")]
+    pub fn new_obj_from_url(url: &str, position: three::Point, dedup: bool, local_origin: bool) -> Result<Model, Box<dyn error::Error>> {
+        // Redirects are followed automatically, and error statuses are reported as errors.
+        let response = match ureq::get(url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, _)) => return Err(Box::from(format!("Failed to download {}: HTTP status {}.", url, code))),
+            Err(error) => return Err(Box::from(format!("Failed to download model: {}.", error))),
+        };
+        if response.status() != 200 {
+            return Err(Box::from(format!("Failed to download {}: HTTP status {}.", url, response.status())));
+        }
+
+        Model::new_obj_from_reader(response.into_reader(), position, dedup, local_origin)?.require_geometry(url)
+    }
+
+    // Returns the model, or an error naming its source if it has no points or edges to draw.
+    fn require_geometry(self, source: &str) -> Result<Model, Box<dyn error::Error>> {
+        if self.points.is_empty() && self.edges.is_empty() {
+            return Err(Box::from(format!("No geometry found in {}.", source)));
+        }
+        Ok(self)
+    }
+
+    // Creates a model from .obj data read from any source, placing it at a specified position in world space.
+    // The data is decompressed first if it is gzip-compressed. Without a directory to look them up in, material libraries are not loaded.
+    // Duplicate edges are removed unless dedup is false, as for new_obj.
+    pub fn new_obj_from_reader<R: io::Read>(
+        reader: R,
+        position: three::Point,
+        dedup: bool,
+        local_origin: bool,
+    ) -> Result<Model, Box<dyn error::Error>> {
+        Model::parse_obj(decompressed(reader)?, None, position, dedup, local_origin)
+    }
+
+    // Reads the materials and their diffuse colors from a .mtl library.
+    fn parse_mtl(path: &path::Path) -> Result<collections::HashMap<String, [f32; 3]>, Box<dyn error::Error>> {
+        // Material names can be in other encodings than UTF-8 too, as in .obj files.
+        let code = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+        let mut colors = collections::HashMap::<String, [f32; 3]>::new();
+        let mut current = None;
+        for line in code.lines() {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("newmtl") => current = tokens.next().map(String::from),
+                Some("Kd") => {
+                    let (name, red, green, blue) = match (&current, tokens.next(), tokens.next(), tokens.next()) {
+                        (Some(name), Some(red), Some(green), Some(blue)) => (name, red, green, blue),
+                        _ => return Err(Box::from(format!("Invalid \"Kd\" statement in {}.", path.display()))),
+                    };
+                    colors.insert(name.clone(), [red.parse::<f32>()?, green.parse::<f32>()?, blue.parse::<f32>()?]);
+                }
+                _ => {}
+            }
+        }
+        Ok(colors)
+    }
+
+    // Parses .obj data, loading any material libraries it references from a directory if one is given, and removing duplicate edges if asked to.
+    // With local_origin, the center of the bounding box is subtracted from the vertices before they are rounded to single precision,
+    // so that models far from the origin, such as geospatial point clouds, keep their detail.
+    fn parse_obj<R: io::Read>(
+        reader: R,
+        directory: Option<&path::Path>,
+        position: three::Point,
+        dedup: bool,
+        local_origin: bool,
+    ) -> Result<Model, Box<dyn error::Error>> {
+        use io::BufRead;
+
+        // Read the .obj data one line at a time, so that only the geometry and not the whole text is held in memory.
+        let mut reader = io::BufReader::new(reader);
+        let mut bytes = Vec::<u8>::new();
+        let mut pending = String::new();
+
+        // Vectors to store parsed vertices (in double precision until the origin is known), lines, faces, and points.
+        let mut coordinates = Vec::<[f64; 3]>::new();
+        let mut normals = Vec::<three::Point>::new();
+        let mut face_normals = Vec::<Vec<Option<usize>>>::new();
         let mut lines = Vec::<Vec<usize>>::new();
         let mut faces = Vec::<Vec<usize>>::new();
+        let mut point_indices = Vec::<usize>::new();
+
+        // Names of the materials selected by "usemtl", the material of each line and face, and the colors from the material libraries.
+        let mut material_names = Vec::<String>::new();
+        let mut material: Option<usize> = None;
+        let mut line_materials = Vec::<Option<usize>>::new();
+        let mut face_materials = Vec::<Option<usize>>::new();
+        let mut material_colors = collections::HashMap::<String, [f32; 3]>::new();
+        let mut library_warnings = Vec::<String>::new();
+
+        // Smoothing group of each face. Faces before any "s" statement are smoothed together, as if the file had no groups.
+        let mut smoothing_group: Option<u32> = Some(0);
+        let mut face_smoothing_groups = Vec::<Option<u32>>::new();
+
+        // Names of the groups selected by "g" and "o", and the group of each line and face.
+        let mut group_names = Vec::<String>::new();
+        let mut group: Option<usize> = None;
+        let mut line_groups = Vec::<Option<usize>>::new();
+        let mut face_groups = Vec::<Option<usize>>::new();
+
+        // Number of statements skipped for each unsupported keyword, reported as warnings once parsing is done.
+        let mut skipped = collections::BTreeMap::<String, usize>::new();
+        let mut curves = 0;
 
         // Iterate through each line in the .obj file.
-        for line in code.split('\n') {
+        loop {
+            bytes.clear();
+            let end_of_file = reader.read_until(b'\n', &mut bytes)? == 0;
+
+            // Some exporters write comments and names in encodings other than UTF-8, so invalid bytes are replaced rather than
+            // failing the whole load, which is safe as the statements themselves are ASCII.
+            pending.push_str(String::from_utf8_lossy(&bytes).trim_end_matches(['\n', '\r']));
+
+            // Join a line ending in a backslash with the line that follows it.
+            if !end_of_file && pending.ends_with('\\') {
+                pending.pop();
+                pending.push(' ');
+                continue;
+            }
+            if end_of_file && pending.is_empty() {
+                break;
+            }
+            let line = mem::take(&mut pending);
+
             // Split the line into tokens (words or numbers).
             let mut tokens = line.split_whitespace().filter(|&line| !line.is_empty());
 
@@ -122,10 +687,10 @@ impl Model {
                     match (tokens.next(), tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
                         (Some(x), Some(y), Some(z), _, None) => {
                             // Parse the x, y, z coordinates and store the vertex.
-                            let x = x.parse::<f32>()?;
-                            let y = y.parse::<f32>()?;
-                            let z = z.parse::<f32>()?;
-                            vertices.push(three::Point::new(x, y, z));
+                            let x = x.parse::<f64>()?;
+                            let y = y.parse::<f64>()?;
+                            let z = z.parse::<f64>()?;
+                            coordinates.push([x, y, z]);
                         }
                         _ => {
                             // If the line format is invalid, return a parsing error.
@@ -144,9 +709,7 @@ impl Model {
                         // Parse the vertex index and add it to the line.
                         match (params.next(), params.next(), params.next()) {
                             (Some(vertex_index), _, None) => {
-                                let vertex_index = vertex_index.parse::<usize>()?;
-                                let vertex_index = vertex_index.checked_sub(1)?;
-                                line.push(vertex_index);
+                                line.push(parse_vertex_index(vertex_index, coordinates.len())?);
                             }
                             _ => {
                                 // Invalid line format.
@@ -157,21 +720,49 @@ impl Model {
 
                     // Add the line to the lines vector.
                     lines.push(line);
+                    line_materials.push(material);
+                    line_groups.push(group);
+                }
+
+                // Approximate free-form curves ("curv") by their control polygon, as evaluating the curves themselves is not supported.
+                // This keeps the geometry of CAD exports visible, although smooth curves appear as straight segments between their control vertices.
+                Some("curv") => {
+                    // Skip the curve's start and end parameter values, which come before the control vertex indices.
+                    match (tokens.next(), tokens.next()) {
+                        (Some(start), Some(end)) => {
+                            start.parse::<f32>()?;
+                            end.parse::<f32>()?;
+                        }
+                        _ => return Err(Box::from(ObjParseError::new())),
+                    }
+
+                    let mut line = Vec::<usize>::new();
+                    for point in tokens {
+                        line.push(parse_vertex_index(point, coordinates.len())?);
+                    }
+                    lines.push(line);
+                    line_materials.push(material);
+                    line_groups.push(group);
+                    curves += 1;
                 }
 
                 // Handle face definitions ("f" or "fo").
                 Some("f") | Some("fo") => {
                     let mut face = Vec::<usize>::new();
+                    let mut corner_normals = Vec::<Option<usize>>::new();
                     for point in tokens {
-                        // Each point in a face refers to a vertex index.
+                        // Each point in a face refers to a vertex index, optionally followed by texture coordinate and normal indices
+                        // ("v/vt/vn", or "v//vn" without texture coordinates).
                         let mut params = point.split('/');
                         
-                        // Parse the vertex index and add it to the face.
+                        // Parse the vertex index and any normal index, and add them to the face.
                         match (params.next(), params.next(), params.next(), params.next()) {
-                            (Some(vertex_index), _, _, None) => {
-                                let vertex_index = vertex_index.parse::<usize>()?;
-                                let vertex_index = vertex_index.checked_sub(1)?;
-                                face.push(vertex_index);
+                            (Some(vertex_index), _, normal_index, None) => {
+                                face.push(parse_vertex_index(vertex_index, coordinates.len())?);
+                                corner_normals.push(match normal_index {
+                                    Some(normal_index) if !normal_index.is_empty() => Some(parse_vertex_index(normal_index, normals.len())?),
+                                    _ => None,
+                                });
                             }
                             _ => {
                                 // Invalid face format.
@@ -182,146 +773,1343 @@ impl Model {
 
                     // Add the face to the faces vector.
                     faces.push(face);
+                    face_normals.push(corner_normals);
+                    face_materials.push(material);
+                    face_smoothing_groups.push(smoothing_group);
+                    face_groups.push(group);
                 }
 
-                // Handle comments (lines starting with "#").
-                Some("#") => {}
+                // Handle point definitions ("p"), which mark vertices to draw as standalone points.
+                Some("p") => {
+                    for point in tokens {
+                        point_indices.push(parse_vertex_index(point, coordinates.len())?);
+                    }
+                }
 
-                // Skip any other unsupported lines.
-                _ => {}
+                // Skip parameter-space vertices ("vp"), which only matter for free-form geometry.
+                Some("vp") => {}
+
+                // Handle comments (lines starting with "#") and blank lines.
+                Some(keyword) if keyword.starts_with('#') => {}
+                None => {}
+
+                // Skip statements that carry no geometry this viewer draws, such as texture coordinates, groups and materials.
+                // Load the colors from material libraries ("mtllib"), which can list several files.
+                Some("mtllib") => {
+                    for name in tokens {
+                        let library = match directory {
+                            Some(directory) => Model::parse_mtl(&directory.join(name)),
+                            None => continue,
+                        };
+                        match library {
+                            Ok(colors) => material_colors.extend(colors),
+                            Err(error) => library_warnings.push(format!("Could not load material library \"{}\": {}", name, error)),
+                        }
+                    }
+                }
+
+                // Select the material ("usemtl") of the lines and faces that follow.
+                Some("usemtl") => {
+                    let name = tokens.next().unwrap_or("");
+                    material = match material_names.iter().position(|existing| existing == name) {
+                        Some(index) => Some(index),
+                        None => {
+                            material_names.push(String::from(name));
+                            Some(material_names.len() - 1)
+                        }
+                    };
+                }
+
+                // Select the smoothing group ("s") of the faces that follow, where "off" or 0 means flat shading.
+                Some("s") => {
+                    smoothing_group = match tokens.next() {
+                        Some("off") | Some("0") => None,
+                        Some(group) => Some(group.parse::<u32>()?),
+                        None => return Err(Box::from(ObjParseError::new())),
+                    };
+                }
+
+                // Select the group ("g") or object ("o") of the lines and faces that follow, where no name means the default group.
+                Some("g") | Some("o") => {
+                    let name = tokens.collect::<Vec<&str>>().join(" ");
+                    group = match (name.is_empty(), group_names.iter().position(|existing| *existing == name)) {
+                        (true, _) => None,
+                        (false, Some(index)) => Some(index),
+                        (false, None) => {
+                            group_names.push(name);
+                            Some(group_names.len() - 1)
+                        }
+                    };
+                }
+
+                // Handle vertex normal definitions ("vn"), which faces can refer to for each of their corners.
+                Some("vn") => {
+                    match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+                        (Some(x), Some(y), Some(z), None) => normals.push(three::Point::new(x.parse::<f32>()?, y.parse::<f32>()?, z.parse::<f32>()?)),
+                        _ => return Err(Box::from(ObjParseError::new())),
+                    }
+                }
+
+                Some("vt") => {}
+
+                // Skip any other unsupported lines, keeping count so they can be reported.
+                Some(keyword) => *skipped.entry(String::from(keyword)).or_insert(0) += 1,
             }
         }
+        let mut warnings: Vec<String> = skipped
+            .iter()
+            .map(|(keyword, count)| format!("Skipped {} unsupported \"{}\" statement(s).", count, keyword))
+            .collect();
+        if curves > 0 {
+            warnings.push(format!("Drew {} free-form curve(s) as straight segments between their control vertices.", curves));
+        }
+        warnings.extend(library_warnings);
 
-        // Convert the parsed lines and faces into edges (pairs of vertex indices).
-        let mut edges = Vec::<(usize, usize)>::new();
-        for line in lines.iter() {
+        // Move the origin to the center of the bounding box if asked to, then round the vertices to single precision relative to it.
+        let mut origin = [0.; 3];
+        if local_origin && !coordinates.is_empty() {
+            for axis in 0..3 {
+                let values = coordinates.iter().map(|vertex| vertex[axis]);
+                let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| (min.min(value), max.max(value)));
+                origin[axis] = (min + max) / 2.;
+            }
+        }
+        let vertices: Vec<three::Point> = coordinates
+            .iter()
+            .map(|vertex| three::Point::new((vertex[0] - origin[0]) as f32, (vertex[1] - origin[1]) as f32, (vertex[2] - origin[2]) as f32))
+            .collect();
+        drop(coordinates);
+
+        // In a file with named groups, lines and faces outside of them, such as before the first "g" or "o", are in the default group.
+        // It comes first, unless the file names a group "default" itself, which they are added to instead.
+        let ungrouped = line_groups.iter().chain(face_groups.iter()).any(Option::is_none);
+        if !group_names.is_empty() && ungrouped {
+            let default_group = match group_names.iter().position(|name| name == DEFAULT_GROUP_NAME) {
+                Some(index) => index,
+                None => {
+                    group_names.insert(0, String::from(DEFAULT_GROUP_NAME));
+                    for group in line_groups.iter_mut().chain(face_groups.iter_mut()).flatten() {
+                        *group += 1;
+                    }
+                    0
+                }
+            };
+            for group in line_groups.iter_mut().chain(face_groups.iter_mut()) {
+                group.get_or_insert(default_group);
+            }
+        }
+
+        // Look up the color of each material now that all of the libraries are loaded.
+        let materials: Vec<Material> = material_names
+            .into_iter()
+            .map(|name| Material { color: material_colors.get(&name).copied(), name })
+            .collect();
+
+        // Convert the parsed lines and faces into edges (pairs of vertex indices), each with the material and group of its line or face.
+        let mut edges = Vec::<(usize, usize, Option<usize>, Option<usize>)>::new();
+        for ((line, &material), &group) in lines.iter().zip(line_materials.iter()).zip(line_groups.iter()) {
             if line.len() >= 2 {
                 for start in 0..line.len() - 1 {
                     let end = start + 1;
-                    edges.push((line[start], line[end]));
+                    edges.push((line[start], line[end], material, group));
                 }
             }
         }
-        for face in faces.iter() {
+        for ((face, &material), &group) in faces.iter().zip(face_materials.iter()).zip(face_groups.iter()) {
             if face.len() >= 2 {
                 for start in 0..face.len() - 1 {
                     let end = start + 1;
-                    edges.push((face[start], face[end]));
+                    edges.push((face[start], face[end], material, group));
                 }
                 // Add the closing edge for the face.
-                edges.push((face.last().unwrap(), face.first().unwrap()));
+                edges.push((*face.last().unwrap(), *face.first().unwrap(), material, group));
             }
         }
 
-        // Remove duplicate edges for performance.
-        edges.sort();
-        edges.dedup();
+        // Make sure every edge and point element refers to an existing vertex.
+        let in_range = |index: usize| index < vertices.len();
+        if !edges.iter().all(|&(start, end, _, _)| in_range(start) && in_range(end)) || !point_indices.iter().all(|&index| in_range(index)) {
+            return Err(Box::from(ObjParseError::new()));
+        }
+        if face_normals.iter().flatten().flatten().any(|&index| index >= normals.len()) {
+            return Err(Box::from(ObjParseError::new()));
+        }
+
+        // Drop zero-length edges, such as from "l 1 1" or from faces repeating a vertex, which would only draw a dot.
+        let edge_count = edges.len();
+        edges.retain(|&(start, end, _, _)| {
+            let (start, end) = (vertices[start], vertices[end]);
+            (start.x, start.y, start.z) != (end.x, end.y, end.z)
+        });
+        let edges_left = edges.len();
+        if edges_left < edge_count {
+            warnings.push(format!("Removed {} zero-length edge(s).", edge_count - edges_left));
+        }
+
+        // Remove duplicate edges for performance, keeping one of the materials and groups of an edge shared by several faces.
+        if dedup {
+            edges.sort();
+            edges.dedup_by_key(|&mut (start, end, _, _)| (start, end));
+        }
+
+        // Split each face into a fan of triangles sharing its first vertex.
+        // This is always right for convex faces, but a concave face whose first vertex can't see all of the others gets triangles
+        // that overlap and stick out past its sides. That shows up in shading and picking, though not in the edges, which come from the faces' sides.
+        let mut triangles = Vec::<[usize; 3]>::new();
+        let mut triangle_materials = Vec::<Option<usize>>::new();
+        let mut triangle_smoothing_groups = Vec::<Option<u32>>::new();
+        let mut triangle_groups = Vec::<Option<usize>>::new();
+        let mut triangle_normals = Vec::<[Option<usize>; 3]>::new();
+        for (index, face) in faces.iter().enumerate() {
+            if face.len() >= 3 {
+                for middle in 1..face.len() - 1 {
+                    triangles.push([face[0], face[middle], face[middle + 1]]);
+                    let corner_normals = &face_normals[index];
+                    triangle_normals.push([corner_normals[0], corner_normals[middle], corner_normals[middle + 1]]);
+                    triangle_materials.push(face_materials[index]);
+                    triangle_smoothing_groups.push(face_smoothing_groups[index]);
+                    triangle_groups.push(face_groups[index]);
+                }
+            }
+        }
+
+        // Only keep the corner normals if the file gives any, so that models without them don't hold a list of Nones.
+        if triangle_normals.iter().flatten().all(Option::is_none) {
+            triangle_normals.clear();
+        }
 
         // Convert the edges from indices to actual points.
+        let edge_materials = edges.iter().map(|&(_, _, material, _)| material).collect();
+        let edge_groups = edges.iter().map(|&(_, _, _, group)| group).collect();
         let edges: Vec<(three::Point, three::Point)> = edges
             .into_iter()
-            .map(|(start_index, end_index)| (vertices[start_index], vertices[end_index]))
+            .map(|(start_index, end_index, _, _)| (vertices[start_index], vertices[end_index]))
             .collect();
 
-        // Return the model with the parsed vertices, edges, and position.
+        // Return the model with the parsed vertices, edges, triangles, and position.
         Ok(Model {
             points: vertices,
             edges,
+            triangles,
+            point_indices,
+            face_normals: Vec::new(),
+            vertex_normals: Vec::new(),
+            normals,
+            triangle_normals,
+            edge_triangles: Vec::new(),
+            winding: None,
+            removed_zero_length_edges: edge_count - edges_left,
+            warnings,
+            materials,
+            edge_materials,
+            triangle_materials,
+            triangle_smoothing_groups,
+            groups: group_names,
+            edge_groups,
+            triangle_groups,
             position,
+            origin,
         })
     }
 
-    // Transforms a point from model space to world space based on the model's position.
-    pub fn model_to_world(&self, point: &three::Point) -> three::Point {
-        three::Point {
-            x: point.x + self.position.x,
-            y: point.y + self.position.y,
-            z: point.z + self.position.z,
+    // Writes the model out as .obj data in world space, with coordinates rounded to a number of decimal places.
+    // Triangles are written as faces ("f"), any other edges as lines ("l") and standalone points as points ("p").
+    // Faces with more than three sides come out split into triangles, and materials, groups and normals are not written.
+    pub fn write_obj<W: io::Write>(&self, out: &mut W, precision: usize) -> io::Result<()> {
+        // Edges store points rather than indices, so look their ends up among the points by their exact coordinates,
+        // adding the ends that aren't points of their own (as in edge-only models).
+        let key = |point: &three::Point| (point.x.to_bits(), point.y.to_bits(), point.z.to_bits());
+        let mut vertices = self.points.clone();
+        let mut indices = collections::HashMap::<(u32, u32, u32), usize>::new();
+        for (index, point) in self.points.iter().enumerate() {
+            indices.entry(key(point)).or_insert(index);
+        }
+        let mut index_of = |point: &three::Point| {
+            *indices.entry(key(point)).or_insert_with(|| {
+                vertices.push(*point);
+                vertices.len() - 1
+            })
+        };
+        let sides = self.edge_face_counts();
+        let lines: Vec<(usize, usize)> = self.edges
+            .iter()
+            .map(|(start, end)| (index_of(start), index_of(end)))
+            .filter(|&(start, end)| !sides.contains_key(&(start.min(end), start.max(end))))
+            .collect();
+
+        // Vertices are written at their coordinates in the file, adding the origin back in double precision.
+        for vertex in vertices.iter() {
+            let vertex = self.model_to_world(vertex);
+            writeln!(
+                out,
+                "v {} {} {}",
+                format_coordinate(vertex.x as f64 + self.origin[0], precision),
+                format_coordinate(vertex.y as f64 + self.origin[1], precision),
+                format_coordinate(vertex.z as f64 + self.origin[2], precision),
+            )?;
+        }
+        for triangle in self.triangles.iter() {
+            writeln!(out, "f {} {} {}", triangle[0] + 1, triangle[1] + 1, triangle[2] + 1)?;
+        }
+        for (start, end) in lines {
+            writeln!(out, "l {} {}", start + 1, end + 1)?;
+        }
+        for index in self.point_indices.iter() {
+            writeln!(out, "p {}", index + 1)?;
         }
+        out.flush()
     }
 
-    // Returns the axis-aligned bounding box (AABB) of the model in world space.
-    pub fn world_bounds(&self) -> (three::Point, three::Point) {
-        // If the model has no points or edges, return a degenerate bounding box.
-        if self.points.is_empty() && self.edges.is_empty() {
-            return (three::Point::new(0., 0., 0.), three::Point::new(0., 0., 0.));
+    // Returns the diffuse color of an edge's material, if it has one.
+    pub fn edge_color(&self, index: usize) -> Option<[f32; 3]> {
+        self.edge_materials.get(index).copied().flatten().and_then(|material| self.materials[material].color)
+    }
+
+    // Returns the diffuse color of a triangle's material, if it has one.
+    pub fn triangle_color(&self, index: usize) -> Option<[f32; 3]> {
+        self.triangle_materials.get(index).copied().flatten().and_then(|material| self.materials[material].color)
+    }
+
+    // Returns the cross product of two sides of a triangle, whose length is twice the triangle's area.
+    fn triangle_cross(&self, triangle: &[usize; 3]) -> three::Point {
+        let [a, b, c] = triangle.map(|index| self.points[index]);
+        (b - a).cross(&(c - a))
+    }
+
+    // Computes the unit normal of each triangle from its winding.
+    // Degenerate (zero-area) triangles get a zero normal so the result still lines up with `triangles`.
+    pub fn compute_face_normals(&self) -> Vec<three::Point> {
+        self.triangles.iter().map(|triangle| self.triangle_cross(triangle).normalized()).collect()
+    }
+
+    // Returns the smoothing group of a triangle, or None if it is shaded flat.
+    pub fn smoothing_group(&self, index: usize) -> Option<u32> {
+        self.triangle_smoothing_groups.get(index).copied().unwrap_or(Some(0))
+    }
+
+    // Computes a unit normal for each corner of each triangle by averaging the normals of the triangles around the corner's point
+    // that are in the same smoothing group, weighted by their area, so that the borders between groups stay creased.
+    // Triangles that are shaded flat use their face normal at every corner, and corners surrounded only by degenerate triangles get a zero normal.
+    // Corners given a normal in the file use that instead, so these are only the fallback for corners without one.
+    pub fn compute_vertex_normals(&self) -> Vec<[three::Point; 3]> {
+        let mut sums = collections::HashMap::<(usize, u32), three::Point>::new();
+        for (index, triangle) in self.triangles.iter().enumerate() {
+            let group = match self.smoothing_group(index) {
+                Some(group) => group,
+                None => continue,
+            };
+            // The unnormalized cross product is already weighted by area, and is zero for degenerate triangles.
+            let cross = self.triangle_cross(triangle);
+            for &point in triangle.iter() {
+                let sum = sums.entry((point, group)).or_insert(three::Point::new(0., 0., 0.));
+                *sum = *sum + cross;
+            }
+        }
+
+        self.triangles
+            .iter()
+            .enumerate()
+            .map(|(index, triangle)| {
+                let computed = match self.smoothing_group(index) {
+                    Some(group) => triangle.map(|point| sums[&(point, group)].normalized()),
+                    None => [self.triangle_cross(triangle).normalized(); 3],
+                };
+                let given = self.triangle_normals.get(index).copied().unwrap_or([None; 3]);
+                [0, 1, 2].map(|corner| given[corner].map_or(computed[corner], |normal| self.normals[normal].normalized()))
+            })
+            .collect()
+    }
+
+    // Fills `face_normals` and `vertex_normals` from the geometry, for shading modes that need them.
+    pub fn cache_normals(&mut self) {
+        self.face_normals = self.compute_face_normals();
+        self.vertex_normals = self.compute_vertex_normals();
+    }
+
+    // Finds up to two triangles that each edge is a side of, with None in place of missing ones, such as for lines.
+    // Edges store points rather than indices, so their ends are matched to the triangles' corners by their exact coordinates.
+    // Sides shared by more than two triangles only keep the first two.
+    pub fn compute_edge_triangles(&self) -> Vec<[Option<usize>; 2]> {
+        let key = |point: &three::Point| (point.x.to_bits(), point.y.to_bits(), point.z.to_bits());
+        let side_key = |start: &three::Point, end: &three::Point| {
+            let (start, end) = (key(start), key(end));
+            (start.min(end), start.max(end))
+        };
+
+        let mut sides = collections::HashMap::<((u32, u32, u32), (u32, u32, u32)), [Option<usize>; 2]>::new();
+        for (index, triangle) in self.triangles.iter().enumerate() {
+            for side in 0..3 {
+                let triangles = sides
+                    .entry(side_key(&self.points[triangle[side]], &self.points[triangle[(side + 1) % 3]]))
+                    .or_insert([None; 2]);
+                if let Some(slot) = triangles.iter_mut().find(|slot| slot.is_none()) {
+                    *slot = Some(index);
+                }
+            }
+        }
+
+        self.edges
+            .iter()
+            .map(|(start, end)| sides.get(&side_key(start, end)).copied().unwrap_or([None; 2]))
+            .collect()
+    }
+
+    // Fills `edge_triangles` and `winding` from the geometry, for ranking edges by whether they are on the model's outline
+    // and for culling back faces.
+    pub fn cache_edge_triangles(&mut self) {
+        self.edge_triangles = self.compute_edge_triangles();
+        self.winding = self.outward_winding();
+    }
+
+    // Smooths the model's triangles with Loop subdivision, splitting every triangle into four on each iteration.
+    // New points are placed on each side and the existing points are moved towards their neighbors, while sides
+    // on the boundary of the mesh (or shared by more than two triangles) are only split, keeping holes and
+    // creases in place. Edges that aren't sides of triangles, such as lines, are kept as they are.
+    pub fn subdivide(&mut self, iterations: u32) {
+        if iterations > MAX_SUBDIVISIONS {
+            self.warnings.push(format!("Subdividing {} times instead of {}, the most allowed.", MAX_SUBDIVISIONS, iterations));
+        }
+        if self.triangles.is_empty() {
+            return;
         }
 
-        // Initialize min and max bounds with the position of the first point in world space.
-        let mut min = self.model_to_world(&self.points[0]);
-        let mut max = min.clone();
+        // Find the edges that aren't triangle sides by comparing their exact coordinates, since edges store points rather than indices.
+        let key = |point: &three::Point| (point.x.to_bits(), point.y.to_bits(), point.z.to_bits());
+        let sides: collections::HashSet<_> = self.edge_face_counts()
+            .keys()
+            .flat_map(|&(start, end)| {
+                let (start, end) = (key(&self.points[start]), key(&self.points[end]));
+                [(start, end), (end, start)]
+            })
+            .collect();
+        let kept: Vec<(usize, (three::Point, three::Point))> = self.edges
+            .iter()
+            .enumerate()
+            .filter(|(_, (start, end))| !sides.contains(&(key(start), key(end))))
+            .map(|(index, &edge)| (index, edge))
+            .collect();
+        let kept_materials: Vec<Option<usize>> = kept
+            .iter()
+            .map(|&(index, _)| self.edge_materials.get(index).copied().flatten())
+            .collect();
+        let kept_groups: Vec<Option<usize>> = kept
+            .iter()
+            .map(|&(index, _)| self.edge_groups.get(index).copied().flatten())
+            .collect();
 
-        // Iterate through all points in the model.
-        for point in &self.points {
-            let point = self.model_to_world(point);
+        // The file's normals don't fit the smoothed surface, so its new corners all get normals computed from it instead.
+        self.triangle_normals.clear();
+        for _ in 0..iterations.min(MAX_SUBDIVISIONS) {
+            self.loop_subdivision_step();
+        }
 
-            // Update the min and max bounds.
-            if point.x < min.x {
-                min.x = point.x;
+        // Rebuild the edges from the sides of the new triangles, each taking the material and group of a triangle it belongs to.
+        let mut edges = Vec::<(usize, usize, Option<usize>, Option<usize>)>::new();
+        for (index, triangle) in self.triangles.iter().enumerate() {
+            let material = self.triangle_materials.get(index).copied().flatten();
+            let group = self.triangle_groups.get(index).copied().flatten();
+            for side in 0..3 {
+                let (start, end) = (triangle[side], triangle[(side + 1) % 3]);
+                edges.push((start.min(end), start.max(end), material, group));
             }
-            if point.y < min.y {
-                min.y = point.y;
+        }
+        edges.sort();
+        edges.dedup_by_key(|&mut (start, end, _, _)| (start, end));
+
+        self.edge_materials = edges.iter().map(|&(_, _, material, _)| material).chain(kept_materials).collect();
+        self.edge_groups = edges.iter().map(|&(_, _, _, group)| group).chain(kept_groups).collect();
+        self.edges = edges
+            .into_iter()
+            .map(|(start, end, _, _)| (self.points[start], self.points[end]))
+            .chain(kept.into_iter().map(|(_, edge)| edge))
+            .collect();
+
+        // Keep any cached normals up to date with the new triangles.
+        if !self.face_normals.is_empty() {
+            self.cache_normals();
+        }
+
+        if self.triangles.len() > LARGE_TRIANGLE_COUNT {
+            self.warnings.push(format!("The subdivided model has {} triangles, which may render slowly.", self.triangles.len()));
+        }
+    }
+
+    // Applies a single iteration of Loop subdivision to the points and triangles.
+    fn loop_subdivision_step(&mut self) {
+        // Weighted sum of points, for placing the new and moved points.
+        let weighted = |terms: &[(f32, three::Point)]| {
+            terms.iter().fold(three::Point::new(0., 0., 0.), |sum, &(weight, point)| {
+                three::Point::new(sum.x + weight * point.x, sum.y + weight * point.y, sum.z + weight * point.z)
+            })
+        };
+
+        // Find the corners opposite each side, one for every triangle sharing the side.
+        let mut opposites = collections::BTreeMap::<(usize, usize), Vec<usize>>::new();
+        for triangle in self.triangles.iter() {
+            for side in 0..3 {
+                let (start, end) = (triangle[side], triangle[(side + 1) % 3]);
+                opposites.entry((start.min(end), start.max(end))).or_default().push(triangle[(side + 2) % 3]);
             }
-            if point.z < min.z {
-                min.z = point.z;
+        }
+
+        // Collect the neighbors of each point, separately noting the ones across boundary sides.
+        let mut neighbors = vec![Vec::<usize>::new(); self.points.len()];
+        let mut boundary_neighbors = vec![Vec::<usize>::new(); self.points.len()];
+        for (&(start, end), opposite) in opposites.iter() {
+            neighbors[start].push(end);
+            neighbors[end].push(start);
+            if opposite.len() != 2 {
+                boundary_neighbors[start].push(end);
+                boundary_neighbors[end].push(start);
             }
-            if point.x > max.x {
-                max.x = point.x;
+        }
+
+        // Move the existing points towards their neighbors. Points on a boundary only follow the boundary,
+        // and points where several boundaries meet, or that belong to no triangle, stay where they are.
+        let mut points: Vec<three::Point> = self.points.iter().enumerate().map(|(index, &point)| {
+            match (boundary_neighbors[index].as_slice(), neighbors[index].len()) {
+                ([], 0) => point,
+                ([], count) => {
+                    let beta = if count == 3 { 3. / 16. } else { 3. / (8. * count as f32) };
+                    let mut terms = vec![(1. - count as f32 * beta, point)];
+                    terms.extend(neighbors[index].iter().map(|&neighbor| (beta, self.points[neighbor])));
+                    weighted(&terms)
+                }
+                (&[a, b], _) => weighted(&[(0.75, point), (0.125, self.points[a]), (0.125, self.points[b])]),
+                _ => point,
             }
-            if point.y > max.y {
-                max.y = point.y;
+        }).collect();
+
+        // Add a point on each side, pulled towards the opposite corners for sides shared by two triangles.
+        let mut midpoints = collections::HashMap::<(usize, usize), usize>::new();
+        for (&(start, end), opposite) in opposites.iter() {
+            let (a, b) = (self.points[start], self.points[end]);
+            let point = match opposite.as_slice() {
+                &[c, d] => weighted(&[(0.375, a), (0.375, b), (0.125, self.points[c]), (0.125, self.points[d])]),
+                _ => weighted(&[(0.5, a), (0.5, b)]),
+            };
+            points.push(point);
+            midpoints.insert((start, end), points.len() - 1);
+        }
+        let midpoint = |start: usize, end: usize| midpoints[&(start.min(end), start.max(end))];
+
+        // Split each triangle into three corner triangles and a middle one, keeping the winding, the material and the smoothing group.
+        let mut triangles = Vec::with_capacity(self.triangles.len() * 4);
+        for &[a, b, c] in self.triangles.iter() {
+            let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+            triangles.extend([[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        self.triangle_materials = self.triangle_materials.iter().flat_map(|&material| [material; 4]).collect();
+        self.triangle_smoothing_groups = self.triangle_smoothing_groups.iter().flat_map(|&group| [group; 4]).collect();
+        self.triangle_groups = self.triangle_groups.iter().flat_map(|&group| [group; 4]).collect();
+        self.triangles = triangles;
+        self.points = points;
+    }
+
+    // Labels the connected components of the model, returning the component of each point and of each edge.
+    // Triangles, edges and points are connected when they share a point, with edges matched to points by their exact coordinates
+    // since they store points rather than indices. Points with the same coordinates are in the same component.
+    fn component_labels(&self) -> (Vec<usize>, Vec<usize>) {
+        // Union-find over the points, followed by a node for each edge end that isn't one of the points.
+        let key = |point: &three::Point| (point.x.to_bits(), point.y.to_bits(), point.z.to_bits());
+        let mut parents: Vec<usize> = (0..self.points.len()).collect();
+        fn find(parents: &mut [usize], mut node: usize) -> usize {
+            while parents[node] != node {
+                parents[node] = parents[parents[node]];
+                node = parents[node];
             }
-            if point.z > max.z {
-                max.z = point.z;
+            node
+        }
+        fn union(parents: &mut [usize], a: usize, b: usize) {
+            let (a, b) = (find(parents, a), find(parents, b));
+            parents[a.max(b)] = a.min(b);
+        }
+
+        // Points with the same coordinates are the same point as far as the edges can tell, so they are joined too.
+        let mut nodes = collections::HashMap::<(u32, u32, u32), usize>::new();
+        for (index, point) in self.points.iter().enumerate() {
+            let node = *nodes.entry(key(point)).or_insert(index);
+            union(&mut parents, node, index);
+        }
+        let mut edge_nodes = Vec::with_capacity(self.edges.len());
+        for (start, end) in self.edges.iter() {
+            let mut node = |point: &three::Point| *nodes.entry(key(point)).or_insert_with(|| {
+                parents.push(parents.len());
+                parents.len() - 1
+            });
+            let (start, end) = (node(start), node(end));
+            union(&mut parents, start, end);
+            edge_nodes.push(start);
+        }
+        for triangle in self.triangles.iter() {
+            union(&mut parents, triangle[0], triangle[1]);
+            union(&mut parents, triangle[0], triangle[2]);
+        }
+
+        let point_labels = (0..self.points.len()).map(|point| find(&mut parents, point)).collect();
+        let edge_labels = edge_nodes.iter().map(|&node| find(&mut parents, node)).collect();
+        (point_labels, edge_labels)
+    }
+
+    // Counts the triangles, edges and standalone points in each connected component, keyed by the labels of component_labels.
+    // Components made only of points that nothing uses are left out.
+    fn component_sizes(&self, point_labels: &[usize], edge_labels: &[usize]) -> collections::HashMap<usize, usize> {
+        let mut sizes = collections::HashMap::<usize, usize>::new();
+        let elements = self.triangles
+            .iter()
+            .map(|triangle| point_labels[triangle[0]])
+            .chain(edge_labels.iter().copied())
+            .chain(self.point_indices.iter().map(|&point| point_labels[point]));
+        for label in elements {
+            *sizes.entry(label).or_insert(0) += 1;
+        }
+        sizes
+    }
+
+    // Measures each connected component by the diagonal of its bounding box in model space, keyed by the labels of component_labels.
+    fn component_extents(&self, point_labels: &[usize], edge_labels: &[usize]) -> collections::HashMap<usize, f32> {
+        let mut bounds = collections::HashMap::<usize, (three::Point, three::Point)>::new();
+        let corners = self.points
+            .iter()
+            .zip(point_labels.iter().copied())
+            .chain(self.edges.iter().zip(edge_labels.iter().copied()).flat_map(|((start, end), label)| [(start, label), (end, label)]));
+        for (point, label) in corners {
+            let (min, max) = bounds.entry(label).or_insert((*point, *point));
+            (*min, *max) = (min.min(point), max.max(point));
+        }
+        bounds.into_iter().map(|(label, (min, max))| (label, (max - min).length())).collect()
+    }
+
+    // Returns the number of connected components of the model, the separate pieces it is made of.
+    pub fn component_count(&self) -> usize {
+        let (point_labels, edge_labels) = self.component_labels();
+        self.component_sizes(&point_labels, &edge_labels).len()
+    }
+
+    // Keeps only the largest connected component of the model, the one spanning the biggest bounding box, dropping the rest,
+    // such as stray geometry floating far from the main body. Components of the same extent are ranked by how many triangles,
+    // edges and standalone points they hold. Returns the number of components that were found.
+    pub fn keep_largest_component(&mut self) -> usize {
+        let (point_labels, edge_labels) = self.component_labels();
+        let sizes = self.component_sizes(&point_labels, &edge_labels);
+        let extents = self.component_extents(&point_labels, &edge_labels);
+        let largest = sizes.iter().max_by(|&(&label, &size), &(&other_label, &other_size)| {
+            extents[&label].total_cmp(&extents[&other_label]).then(size.cmp(&other_size)).then(other_label.cmp(&label))
+        });
+        let largest = match largest {
+            Some((&label, _)) => label,
+            None => return 0,
+        };
+
+        let kept_points: Vec<bool> = point_labels.iter().map(|&label| label == largest).collect();
+        let kept_triangles: Vec<bool> = self.triangles.iter().map(|triangle| kept_points[triangle[0]]).collect();
+        let kept_edges: Vec<bool> = edge_labels.iter().map(|&label| label == largest).collect();
+        self.retain_elements(&kept_points, &kept_triangles, &kept_edges);
+
+        sizes.len()
+    }
+
+    // Returns a copy of the model without the edges and triangles in some of its groups or materials, given by their indices,
+    // for hiding parts of it. Points are hidden along with the triangles when every triangle using them is hidden.
+    pub fn without_hidden(&self, hidden_groups: &[usize], hidden_materials: &[usize]) -> Model {
+        let hidden = |group: Option<usize>, material: Option<usize>| {
+            group.is_some_and(|group| hidden_groups.contains(&group)) || material.is_some_and(|material| hidden_materials.contains(&material))
+        };
+        let kept_triangles: Vec<bool> = (0..self.triangles.len())
+            .map(|index| !hidden(self.triangle_groups.get(index).copied().flatten(), self.triangle_materials.get(index).copied().flatten()))
+            .collect();
+        let kept_edges: Vec<bool> = (0..self.edges.len())
+            .map(|index| !hidden(self.edge_groups.get(index).copied().flatten(), self.edge_materials.get(index).copied().flatten()))
+            .collect();
+
+        // A point stays unless it is only used by hidden triangles.
+        let mut used = vec![None; self.points.len()];
+        for (triangle, &kept) in self.triangles.iter().zip(kept_triangles.iter()) {
+            for &point in triangle.iter() {
+                *used[point].get_or_insert(false) |= kept;
             }
         }
+        let kept_points: Vec<bool> = used.iter().map(|used| used.unwrap_or(true)).collect();
 
-        // Iterate through all edges and include their points in the bounding box.
-        for (start, end) in &self.edges {
-            let start = self.model_to_world(start);
-            let end = self.model_to_world(end);
+        let mut model = self.clone();
+        model.retain_elements(&kept_points, &kept_triangles, &kept_edges);
+        model
+    }
 
-            // Update bounds for the start and end points of the edge.
-            if start.x < min.x {
-                min.x = start.x;
+    // Keeps only the flagged points, triangles and edges, along with everything lined up with them, renumbering the points that are left.
+    // Triangles using any of the dropped points are dropped too.
+    fn retain_elements(&mut self, kept_points: &[bool], kept_triangles: &[bool], kept_edges: &[bool]) {
+        let mut new_indices = vec![None; self.points.len()];
+        let mut points = Vec::new();
+        for (index, new_index) in new_indices.iter_mut().enumerate() {
+            if kept_points[index] {
+                *new_index = Some(points.len());
+                points.push(self.points[index]);
             }
-            if start.y < min.y {
-                min.y = start.y;
+        }
+        let kept_triangles: Vec<bool> = self.triangles
+            .iter()
+            .zip(kept_triangles.iter())
+            .map(|(triangle, &kept)| kept && triangle.iter().all(|&point| new_indices[point].is_some()))
+            .collect();
+
+        fn retain<T>(values: &mut Vec<T>, kept: &[bool]) {
+            if !values.is_empty() {
+                let mut flags = kept.iter();
+                values.retain(|_| *flags.next().unwrap());
             }
-            if start.z < min.z {
-                min.z = start.z;
+        }
+        retain(&mut self.triangles, &kept_triangles);
+        retain(&mut self.face_normals, &kept_triangles);
+        retain(&mut self.vertex_normals, &kept_triangles);
+        retain(&mut self.triangle_normals, &kept_triangles);
+        retain(&mut self.triangle_materials, &kept_triangles);
+        retain(&mut self.triangle_smoothing_groups, &kept_triangles);
+        retain(&mut self.triangle_groups, &kept_triangles);
+        retain(&mut self.edges, kept_edges);
+        retain(&mut self.edge_triangles, kept_edges);
+        retain(&mut self.edge_materials, kept_edges);
+        retain(&mut self.edge_groups, kept_edges);
+        for triangle in self.triangles.iter_mut() {
+            *triangle = triangle.map(|point| new_indices[point].unwrap());
+        }
+        self.point_indices = self.point_indices.iter().filter_map(|&point| new_indices[point]).collect();
+        self.points = points;
+        if !self.edge_triangles.is_empty() {
+            self.cache_edge_triangles();
+        }
+    }
+
+    // Checks the model for common mesh problems, such as before 3D printing it.
+    pub fn validate(&self) -> Validation {
+        let counts = self.edge_face_counts();
+        let (flips, non_orientable_pieces) = self.winding_flips();
+        let key = |point: &three::Point| (point.x.to_bits(), point.y.to_bits(), point.z.to_bits());
+        let mut seen = collections::HashSet::<(u32, u32, u32)>::new();
+
+        Validation {
+            non_manifold_edges: counts.values().filter(|&&count| count > 2).count(),
+            boundary_edges: counts.values().filter(|&&count| count == 1).count(),
+            duplicate_vertices: self.points.iter().filter(|point| !seen.insert(key(point))).count(),
+            degenerate_triangles: self.triangles
+                .iter()
+                .filter(|&&[a, b, c]| {
+                    let (a, b, c) = (self.points[a], self.points[b], self.points[c]);
+                    (b - a).cross(&(c - a)).length() == 0.
+                })
+                .count(),
+            components: self.component_count(),
+            flipped_triangles: flips.iter().filter(|&&flip| flip).count(),
+            non_orientable_pieces,
+            removed_zero_length_edges: self.removed_zero_length_edges,
+        }
+    }
+
+    // Finds the triangles to turn over so that neighboring triangles are wound the same way, and counts the pieces where that can't be done.
+    // Triangles are neighbors when they are the only two sharing a side, and agree when they go along it in opposite directions.
+    // The winding of each piece of neighboring triangles is flooded from its first triangle, and whichever winding fewer of its
+    // triangles have is the one marked to turn over. Pieces that can't be wound consistently are left as they are.
+    fn winding_flips(&self) -> (Vec<bool>, usize) {
+        // The triangles along each side, with whether each goes from the side's smaller point index to its larger one.
+        let mut sides = collections::HashMap::<(usize, usize), Vec<(usize, bool)>>::new();
+        for (index, triangle) in self.triangles.iter().enumerate() {
+            for side in 0..3 {
+                let (start, end) = (triangle[side], triangle[(side + 1) % 3]);
+                sides.entry((start.min(end), start.max(end))).or_default().push((index, start < end));
             }
-            if start.x > max.x {
-                max.x = start.x;
+        }
+
+        // The neighbors of each triangle, with whether the neighbor has to be turned over to agree with it.
+        let mut neighbors = vec![Vec::<(usize, bool)>::new(); self.triangles.len()];
+        for triangles in sides.values() {
+            if let &[(first, first_forward), (second, second_forward)] = triangles.as_slice() {
+                neighbors[first].push((second, first_forward == second_forward));
+                neighbors[second].push((first, first_forward == second_forward));
             }
-            if start.y > max.y {
-                max.y = start.y;
+        }
+
+        let mut turned: Vec<Option<bool>> = vec![None; self.triangles.len()];
+        let mut flips = vec![false; self.triangles.len()];
+        let mut non_orientable_pieces = 0;
+        for seed in 0..self.triangles.len() {
+            if turned[seed].is_some() {
+                continue;
             }
-            if start.z > max.z {
-                max.z = start.z;
+
+            // Flood out from the seed, turning each triangle so that it agrees with the one it was reached from.
+            turned[seed] = Some(false);
+            let mut piece = vec![seed];
+            let mut stack = vec![seed];
+            let mut orientable = true;
+            while let Some(triangle) = stack.pop() {
+                let triangle_turned = turned[triangle] == Some(true);
+                for &(neighbor, disagrees) in neighbors[triangle].iter() {
+                    match turned[neighbor] {
+                        None => {
+                            turned[neighbor] = Some(triangle_turned != disagrees);
+                            piece.push(neighbor);
+                            stack.push(neighbor);
+                        }
+                        Some(neighbor_turned) => orientable &= neighbor_turned == (triangle_turned != disagrees),
+                    }
+                }
             }
 
-            if end.x < min.x {
-                min.x = end.x;
+            if !orientable {
+                non_orientable_pieces += 1;
+                continue;
             }
-            if end.y < min.y {
-                min.y = end.y;
+            let turned_count = piece.iter().filter(|&&triangle| turned[triangle] == Some(true)).count();
+            let keep_turned = turned_count * 2 > piece.len();  // Turn over the triangles that weren't turned when they are fewer.
+            for &triangle in piece.iter() {
+                flips[triangle] = (turned[triangle] == Some(true)) != keep_turned;
             }
-            if end.z < min.z {
-                min.z = end.z;
+        }
+        (flips, non_orientable_pieces)
+    }
+
+    // Turns over the triangles wound the other way around from their neighbors, so each piece of the model is wound consistently.
+    // Returns the number of triangles turned over. Cached normals are not updated, so this should come before computing them.
+    pub fn fix_winding(&mut self) -> usize {
+        let (flips, _) = self.winding_flips();
+        for (index, &flip) in flips.iter().enumerate() {
+            if flip {
+                self.triangles[index].swap(1, 2);
+                if let Some(corner_normals) = self.triangle_normals.get_mut(index) {
+                    corner_normals.swap(1, 2);
+                }
             }
-            if end.x > max.x {
-                max.x = end.x;
+        }
+        flips.iter().filter(|&&flip| flip).count()
+    }
+
+    // Finds which way round the triangles of a closed mesh are wound: Some(true) when their cross products point out of the mesh,
+    // and Some(false) when they all point in. Returns None unless every triangle side is shared by exactly two triangles going along it
+    // in opposite directions, as only then do the triangles enclose a space whose outside they all face the same way.
+    pub fn outward_winding(&self) -> Option<bool> {
+        let mut sides = collections::HashSet::<(usize, usize)>::with_capacity(self.triangles.len() * 3);
+        for triangle in self.triangles.iter() {
+            for side in 0..3 {
+                if !sides.insert((triangle[side], triangle[(side + 1) % 3])) {
+                    return None;
+                }
             }
-            if end.y > max.y {
-                max.y = end.y;
+        }
+        if sides.is_empty() || !sides.iter().all(|&(start, end)| sides.contains(&(end, start))) {
+            return None;
+        }
+
+        // The volume enclosed by the triangles, signed by their winding, is positive when their cross products point outwards.
+        let volume: f32 = self.triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|index| self.points[index]);
+                a.dot(&b.cross(&c))
+            })
+            .sum();
+        Some(volume > 0.)
+    }
+
+    // Counts the triangles sharing each triangle side, keyed by the side's point indices with the smaller index first.
+    // In a closed manifold mesh every side is shared by exactly two triangles.
+    pub fn edge_face_counts(&self) -> collections::HashMap<(usize, usize), usize> {
+        let mut counts = collections::HashMap::<(usize, usize), usize>::new();
+        for triangle in self.triangles.iter() {
+            for side in 0..3 {
+                let (start, end) = (triangle[side], triangle[(side + 1) % 3]);
+                *counts.entry((start.min(end), start.max(end))).or_insert(0) += 1;
             }
-            if end.z > max.z {
-                max.z = end.z;
+        }
+        counts
+    }
+
+    // Makes the center of the bounding box the origin of model space, like center_to_origin, but adds the offset to `origin`
+    // so that the model is still written out where it was.
+    pub fn move_origin_to_center(&mut self) {
+        let (min, max) = self.world_bounds();
+        let offset = (min + max) * 0.5 - self.position;
+
+        self.transform(|point| point - offset);
+        self.origin = [self.origin[0] + offset.x as f64, self.origin[1] + offset.y as f64, self.origin[2] + offset.z as f64];
+    }
+
+    // Shifts every vertex so the center of the bounding box sits at (0, 0, 0) in model space.
+    // This actually moves the model: afterwards it is centered on `position`, which is the world origin for most models.
+    pub fn center_to_origin(&mut self) {
+        let (min, max) = self.world_bounds();
+        let offset = (min + max) * 0.5 - self.position;
+
+        self.transform(|point| point - offset);
+    }
+
+    // Applies a transformation to every vertex in model space, including the endpoints of the edges.
+    // Neither the cached normals nor the normals from the file are updated, so callers that change the model's orientation need to
+    // turn the file's normals with it and recompute the cached ones.
+    pub fn transform<F: Fn(three::Point) -> three::Point>(&mut self, transformation: F) {
+        for point in self.points.iter_mut() {
+            *point = transformation(*point);
+        }
+        for (start, end) in self.edges.iter_mut() {
+            *start = transformation(*start);
+            *end = transformation(*end);
+        }
+    }
+
+    // Mirrors the model by negating one coordinate (0 for x, 1 for y, 2 for z) of every vertex in model space.
+    // Mirroring turns the triangles inside out, so their winding is reversed to keep them facing outwards. Flipping twice leaves the model unchanged.
+    pub fn flip(&mut self, axis: usize) {
+        let mirror = |point: three::Point| match axis {
+            0 => three::Point::new(-point.x, point.y, point.z),
+            1 => three::Point::new(point.x, -point.y, point.z),
+            _ => three::Point::new(point.x, point.y, -point.z),
+        };
+        self.transform(mirror);
+        self.normals = self.normals.iter().map(|&normal| mirror(normal)).collect();
+        for triangle in self.triangles.iter_mut() {
+            triangle.swap(1, 2);
+        }
+        for corner_normals in self.triangle_normals.iter_mut() {
+            corner_normals.swap(1, 2);
+        }
+        if !self.face_normals.is_empty() {
+            self.cache_normals();
+        }
+    }
+
+    // Turns a model made with the Z axis pointing up so that it stands upright in this viewer, where the Y axis points up.
+    // This is a rotation rather than a mirror, so the triangles keep facing outwards.
+    pub fn z_up_to_y_up(&mut self) {
+        let rotation = |point: three::Point| three::Point::new(point.x, point.z, -point.y);
+        self.transform(rotation);
+        self.normals = self.normals.iter().map(|&normal| rotation(normal)).collect();
+        if !self.face_normals.is_empty() {
+            self.cache_normals();
+        }
+    }
+
+    // Transforms a point from model space to world space based on the model's position.
+    pub fn model_to_world(&self, point: &three::Point) -> three::Point {
+        *point + self.position
+    }
+
+    // Returns a copy of the model for an exploded view, with each group pushed away from the center of the bounding box
+    // by its centroid's offset from the center, scaled by a factor. A factor of 0 leaves the model assembled.
+    // Points move with the group of the first triangle using them, and geometry outside of any group stays in place.
+    pub fn exploded(&self, factor: f32) -> Model {
+        let zero = three::Point::new(0., 0., 0.);
+
+        // Add up the points of each group's edges and triangles to find the groups' centroids.
+        let mut sums = vec![(zero, 0); self.groups.len()];
+        for (index, &(start, end)) in self.edges.iter().enumerate() {
+            if let Some(group) = self.edge_groups.get(index).copied().flatten() {
+                sums[group] = (sums[group].0 + start + end, sums[group].1 + 2);
             }
         }
+        for (index, triangle) in self.triangles.iter().enumerate() {
+            if let Some(group) = self.triangle_groups.get(index).copied().flatten() {
+                let [a, b, c] = triangle.map(|point| self.points[point]);
+                sums[group] = (sums[group].0 + a + b + c, sums[group].1 + 3);
+            }
+        }
+
+        let (min, max) = self.world_bounds();
+        let center = (min + max) * 0.5 - self.position;
+        let offsets: Vec<three::Point> = sums
+            .iter()
+            .map(|&(sum, count)| if count == 0 { zero } else { (sum * (1. / count as f32) - center) * factor })
+            .collect();
+        let offset = |group: Option<usize>| group.map_or(zero, |group| offsets[group]);
+
+        let mut point_offsets = vec![None; self.points.len()];
+        for (index, triangle) in self.triangles.iter().enumerate() {
+            for &point in triangle.iter() {
+                point_offsets[point].get_or_insert(offset(self.triangle_groups.get(index).copied().flatten()));
+            }
+        }
+
+        Model {
+            points: self.points.iter().zip(point_offsets).map(|(&point, point_offset)| point + point_offset.unwrap_or(zero)).collect(),
+            edges: self.edges
+                .iter()
+                .enumerate()
+                .map(|(index, &(start, end))| {
+                    let edge_offset = offset(self.edge_groups.get(index).copied().flatten());
+                    (start + edge_offset, end + edge_offset)
+                })
+                .collect(),
+            triangles: self.triangles.clone(),
+            point_indices: self.point_indices.clone(),
+            face_normals: self.face_normals.clone(),
+            vertex_normals: self.vertex_normals.clone(),
+            normals: self.normals.clone(),
+            triangle_normals: self.triangle_normals.clone(),
+            edge_triangles: self.edge_triangles.clone(),
+            winding: self.winding,
+            removed_zero_length_edges: self.removed_zero_length_edges,
+            warnings: Vec::new(),
+            materials: self.materials.clone(),
+            edge_materials: self.edge_materials.clone(),
+            triangle_materials: self.triangle_materials.clone(),
+            triangle_smoothing_groups: self.triangle_smoothing_groups.clone(),
+            groups: self.groups.clone(),
+            edge_groups: self.edge_groups.clone(),
+            triangle_groups: self.triangle_groups.clone(),
+            position: self.position,
+            origin: self.origin,
+        }
+    }
+
+    // Drops the edges whose ends have ended up on the same point, such as after moving the points of a model with tiny edges
+    // far from the origin, so that they never reach the renderer. Returns how many were dropped, which is also added to the model's count.
+    pub fn remove_zero_length_edges(&mut self) -> usize {
+        let kept_edges: Vec<bool> = self.edges.iter().map(|(start, end)| (start.x, start.y, start.z) != (end.x, end.y, end.z)).collect();
+        let removed = kept_edges.iter().filter(|&&kept| !kept).count();
+        if removed > 0 {
+            // Keep the per-edge lists lined up with the edges that are left, leaving the empty ones empty.
+            fn retain<T>(values: &mut Vec<T>, kept: &[bool]) {
+                if !values.is_empty() {
+                    let mut flags = kept.iter();
+                    values.retain(|_| *flags.next().unwrap());
+                }
+            }
+            retain(&mut self.edges, &kept_edges);
+            retain(&mut self.edge_triangles, &kept_edges);
+            retain(&mut self.edge_materials, &kept_edges);
+            retain(&mut self.edge_groups, &kept_edges);
+            self.removed_zero_length_edges += removed;
+        }
+        removed
+    }
+
+    // Returns the 12 edges of the model's bounding box in world space, laid out like the edges of new_cube.
+    pub fn world_bounds_edges(&self) -> Vec<(three::Point, three::Point)> {
+        let (min, max) = self.world_bounds();
+        let corner = |x: f32, y: f32, z: f32| three::Point::new(x, y, z);
+        let front = (corner(min.x, min.y, max.z), corner(min.x, max.y, max.z), corner(max.x, max.y, max.z), corner(max.x, min.y, max.z));
+        let rear = (corner(min.x, min.y, min.z), corner(min.x, max.y, min.z), corner(max.x, max.y, min.z), corner(max.x, min.y, min.z));
+
+        vec![
+            // Front face edges.
+            (front.0, front.1),
+            (front.1, front.2),
+            (front.2, front.3),
+            (front.3, front.0),
+
+            // Rear face edges.
+            (rear.0, rear.1),
+            (rear.1, rear.2),
+            (rear.2, rear.3),
+            (rear.3, rear.0),
+
+            // Edges connecting front and rear faces.
+            (rear.0, front.0),
+            (rear.1, front.1),
+            (rear.2, front.2),
+            (rear.3, front.3),
+        ]
+    }
+
+    // Returns the number of points (vertices) in the model.
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    // Returns the number of edges in the model, including the sides of its triangles.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    // Returns the number of triangles in the model, counting faces with more sides as the triangles they were split into.
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    // Returns the counts of the model's points, edges and triangles together.
+    pub fn summary(&self) -> Summary {
+        Summary {
+            points: self.point_count(),
+            edges: self.edge_count(),
+            triangles: self.triangle_count(),
+            removed_zero_length_edges: self.removed_zero_length_edges,
+        }
+    }
+
+    // Returns the axis-aligned bounding box (AABB) of the model in world space, which like everything else in world space
+    // is relative to `origin` for models loaded with their origin moved to their center.
+    pub fn world_bounds(&self) -> (three::Point, three::Point) {
+        // Include the points of the model as well as the ends of its edges, as edge-only models have no points.
+        let mut points = self.points
+            .iter()
+            .chain(self.edges.iter().flat_map(|(start, end)| [start, end]))
+            .map(|point| self.model_to_world(point));
+
+        // If the model has no points or edges, return a degenerate bounding box.
+        let first = match points.next() {
+            Some(point) => point,
+            None => return (three::Point::new(0., 0., 0.), three::Point::new(0., 0., 0.)),
+        };
+
+        // Grow the bounding box from the first point to include every other one.
+        points.fold((first, first), |(min, max), point| (min.min(&point), max.max(&point)))
+    }
+
+    // Returns the axis-aligned bounding box of one of the model's groups in world space, from its edges and triangles,
+    // or None if the group has neither.
+    pub fn group_bounds(&self, group: usize) -> Option<(three::Point, three::Point)> {
+        let edge_points = self.edges
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| self.edge_groups.get(index).copied().flatten() == Some(group))
+            .flat_map(|(_, &(start, end))| [start, end]);
+        let triangle_points = self.triangles
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| self.triangle_groups.get(index).copied().flatten() == Some(group))
+            .flat_map(|(_, triangle)| triangle.map(|index| self.points[index]));
+        let mut points = edge_points.chain(triangle_points).map(|point| self.model_to_world(&point));
+
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), point| (min.min(&point), max.max(&point))))
+    }
+}
+
+// Parses a vertex index of an .obj statement into a 0-based index, given the number of vertices defined before the statement.
+// Positive indices count from 1 at the first vertex, and negative ones count back from -1 at the most recent vertex.
+fn parse_vertex_index(text: &str, vertex_count: usize) -> Result<usize, Box<dyn error::Error>> {
+    let index = text.parse::<isize>()?;
+    let index = match index {
+        1.. => Some(index as usize - 1),
+        ..=-1 => vertex_count.checked_sub(index.unsigned_abs()),
+        0 => None,
+    };
+    Ok(index.ok_or(ObjParseError::new())?)
+}
+
+// Formats a coordinate rounded to a number of decimal places, without trailing zeros or a negative sign on zero.
+fn format_coordinate(value: f64, precision: usize) -> String {
+    let mut text = format!("{:.*}", precision, value);
+    if text.contains('.') {
+        text.truncate(text.trim_end_matches('0').trim_end_matches('.').len());
+    }
+    if text == "-0" {
+        text = String::from("0");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screen;
+
+    // Parses the text of an .obj file at the origin, with duplicate edges removed and the coordinates kept as they are.
+    fn parse(text: &str) -> Model {
+        Model::new_obj_from_reader(text.as_bytes(), three::Point::new(0., 0., 0.), true, false).unwrap()
+    }
+
+    #[test]
+    fn lines_back_to_the_same_vertex_leave_no_edges() {
+        let model = parse("v 0 0 0\nv 1 0 0\nl 1 1\n");
+        assert!(model.edges.is_empty());
+        assert_eq!(model.removed_zero_length_edges, 1);
+        assert_eq!(model.validate().removed_zero_length_edges, 1);
+        assert_eq!(model.summary().removed_zero_length_edges, 1);
+    }
+
+    #[test]
+    fn edges_that_become_zero_length_are_dropped() {
+        let mut model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nl 1 2\nl 1 3\ng top\nl 2 3\n");
+        model.transform(|point| three::Point::new(point.x, 0., point.z));
+        assert_eq!(model.remove_zero_length_edges(), 1);
+        assert_eq!(model.edges.len(), 2);
+        assert_eq!(model.edge_groups.len(), 2);
+        assert_eq!(model.removed_zero_length_edges, 1);
+    }
+
+    #[test]
+    fn grids_are_centered_on_their_position() {
+        let model = Model::new_grid(2., 4, three::Point::new(1., 2., 3.));
+        assert_eq!(model.points.len(), 25);
+        assert_eq!(model.edges.len(), 10);
+
+        let (min, max) = model.world_bounds();
+        assert_eq!((min.x, min.y, min.z), (0., 2., 2.));
+        assert_eq!((max.x, max.y, max.z), (2., 2., 4.));
+    }
+
+    #[test]
+    fn triangle_faces_are_closed_by_their_last_edge() {
+        let model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n");
+        assert_eq!(model.edges.len(), 3);
+        let (first, last) = (model.points[0], model.points[2]);
+        assert!(model.edges.iter().any(|(start, end)| {
+            let ends = [(start.x, start.y, start.z), (end.x, end.y, end.z)];
+            ends.contains(&(first.x, first.y, first.z)) && ends.contains(&(last.x, last.y, last.z))
+        }));
+    }
+
+    #[test]
+    fn point_elements_are_kept_and_shown() {
+        let model = parse("v -0.5 0 0\nv 0.5 0 0\nvp 0.5 0.5\np 1 2\n");
+        assert!(model.edges.is_empty());
+        assert_eq!(model.point_indices, vec![0, 1]);
+
+        let mut camera = three::Camera::new(three::Point::new(0., 0., -3.), 0., 0., 0., 0.1, 1.2);
+        camera.screen.fit_to_cells::<screen::BrailePixel>(40, 20);
+        camera.screen.clear();
+        camera.plot_model_edges(&model);
+        let text = camera.screen.to_plain_string::<screen::BrailePixel>();
+        assert_eq!(text.chars().filter(|&character| !matches!(character, ' ' | '\u{2800}' | '\n')).count(), 2);
+    }
+
+    #[test]
+    fn polyhedra_are_closed_and_wound_outwards_on_their_sphere() {
+        let origin = three::Point::new(0., 0., 0.);
+        let polyhedra = [
+            (Model::new_tetrahedron(2., origin), 4),
+            (Model::new_octahedron(2., origin), 8),
+            (Model::new_icosahedron(2., origin), 20),
+            (Model::new_icosphere(2., 2, origin), 320),
+        ];
+        for (model, triangles) in polyhedra {
+            assert_eq!(model.triangles.len(), triangles);
+            assert_eq!(model.outward_winding(), Some(true));
+            assert!(model.points.iter().all(|point| (point.length() - 2.).abs() < 1e-5));
+        }
+        assert_eq!(Model::new_icosphere(1., 2, origin).points.len(), 162);
+    }
+
+    #[test]
+    fn subdividing_a_triangle_once_makes_four() {
+        let mut model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n");
+        model.subdivide(1);
+        assert_eq!(model.triangles.len(), 4);
+        assert_eq!(model.points.len(), 6);
+        assert_eq!(model.edges.len(), 9);
+    }
+
+    #[test]
+    fn files_without_geometry_are_errors() {
+        for (name, text) in [("empty.obj", ""), ("comments.obj", "# Exported with no objects\n\n# mtllib scene.mtl\n")] {
+            let path = env::temp_dir().join(format!("termite-{}-{}", process::id(), name));
+            fs::write(&path, text).unwrap();
+            let result = Model::new_obj(path.to_str().unwrap(), three::Point::new(0., 0., 0.), true, false);
+            fs::remove_file(&path).unwrap();
+            assert_eq!(result.err().unwrap().to_string(), format!("No geometry found in {}.", path.display()));
+        }
+    }
+
+    #[test]
+    fn flipping_twice_leaves_the_model_unchanged() {
+        let mut model = Model::new_icosahedron(1., three::Point::new(0., 0., 0.));
+        let coordinates = |model: &Model| model.points.iter().map(|point| (point.x, point.y, point.z)).collect::<Vec<_>>();
+        let (points, triangles) = (coordinates(&model), model.triangles.clone());
+
+        model.flip(0);
+        assert_ne!(coordinates(&model), points);
+        assert_eq!(model.outward_winding(), Some(true));
+        model.flip(0);
+        assert_eq!(coordinates(&model), points);
+        assert_eq!(model.triangles, triangles);
+    }
+
+    #[test]
+    fn smoothing_groups_keep_the_crease_between_them() {
+        // Two triangles at a right angle, sharing the edge from the first vertex to the second.
+        let faces = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\ns 1\nf 1 2 3\ns 2\nf 1 4 2\n";
+        let mut model = parse(faces);
+        model.cache_normals();
+        assert_eq!(model.triangle_smoothing_groups, vec![Some(1), Some(2)]);
+        for (corners, face) in model.vertex_normals.iter().zip(&model.face_normals) {
+            assert!(corners.iter().all(|corner| (*corner - *face).length() < 1e-5));
+        }
+
+        let mut model = parse(&faces.replace("s 2", "s 1"));
+        model.cache_normals();
+        let shared = model.vertex_normals[0][0];
+        assert!((shared - model.face_normals[0]).length() > 0.1 && (shared - model.face_normals[1]).length() > 0.1);
+    }
+
+    #[test]
+    fn non_utf8_comments_do_not_stop_the_geometry_loading() {
+        let bytes = b"# Caf\xe9 model\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        let model = Model::new_obj_from_reader(&bytes[..], three::Point::new(0., 0., 0.), true, false).unwrap();
+        assert_eq!(model.points.len(), 3);
+        assert_eq!(model.triangles.len(), 1);
+    }
+
+    #[test]
+    fn only_the_largest_of_two_cubes_is_kept() {
+        // Writes a cube with faces centered on a point along x, numbering its vertices after the ones before it in the file.
+        let cube = |center: f32, half_side: f32, first: usize| {
+            let corners = [(-1., -1., -1.), (1., -1., -1.), (1., 1., -1.), (-1., 1., -1.), (-1., -1., 1.), (1., -1., 1.), (1., 1., 1.), (-1., 1., 1.)];
+            let faces = [[1, 4, 3, 2], [5, 6, 7, 8], [1, 2, 6, 5], [2, 3, 7, 6], [3, 4, 8, 7], [4, 1, 5, 8]];
+            let vertices = corners.map(|(x, y, z): (f32, f32, f32)| format!("v {} {} {}\n", center + x * half_side, y * half_side, z * half_side));
+            let faces = faces.map(|face| format!("f {}\n", face.map(|index| (index + first).to_string()).join(" ")));
+            vertices.concat() + &faces.concat()
+        };
+
+        // The cubes hold the same geometry, so the small stray one comes first in the file, where it would win a tie.
+        let mut model = parse(&(cube(100., 0.25, 0) + &cube(0., 1., 8)));
+        let big = parse(&cube(0., 1., 0));
+        assert_eq!(model.keep_largest_component(), 2);
+
+        let coordinates = |model: &Model| model.points.iter().map(|point| (point.x, point.y, point.z)).collect::<Vec<_>>();
+        assert_eq!(coordinates(&model), coordinates(&big));
+        assert_eq!(model.triangles, big.triangles);
+        assert_eq!(model.edges.len(), big.edges.len());
+        assert!(model.world_bounds().1.x < 50.);
+    }
+
+    #[test]
+    fn geometry_before_the_first_group_is_in_the_default_group() {
+        let model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 2 3\ng part\nf 1 2 4\ng\nl 3 4\n");
+        assert_eq!(model.groups, vec!["default".to_string(), "part".to_string()]);
+        assert_eq!(model.triangle_groups, vec![Some(0), Some(1)]);
+        assert!(model.edge_groups.iter().all(|group| group.is_some()));
+
+        let mut camera = three::Camera::new(three::Point::new(0.3, 0.3, -3.), 0., 0., 0., 0.1, 1.2);
+        let mut options = three::RenderOptions::new();
+        options.headless = Some((40, 20, screen::OutputFormat::Plain));
+        let (_, stats) = camera.render_frame(&model, &options);
+        assert_eq!(stats.edges_drawn, model.edges.len());
+
+        // Files without groups don't get a default group either.
+        assert!(parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").groups.is_empty());
+    }
+
+    #[test]
+    fn a_local_origin_keeps_far_away_vertices_apart() {
+        let text = "v 1000000.001 2000000.002 3000000.003\nv 1000000.011 2000000.002 3000000.003\nv 1000000.001 2000000.012 3000000.003\nf 1 2 3\n";
+        let origin = three::Point::new(0., 0., 0.);
+        let far = Model::new_obj_from_reader(text.as_bytes(), origin, true, false).unwrap();
+        let local = Model::new_obj_from_reader(text.as_bytes(), origin, true, true).unwrap();
+
+        // Around a million, f32 only has steps of 1/16, so the triangle collapses unless it is moved near the origin first.
+        assert_eq!(far.points[0].x, far.points[1].x);
+        assert!((local.points[1].x - local.points[0].x - 0.01).abs() < 1e-5);
+        assert!((local.points[2].y - local.points[0].y - 0.01).abs() < 1e-5);
+        assert!(local.edges.iter().all(|&(start, end)| (end - start).length() > 0.009));
+        assert!((local.origin[0] - 1000000.006).abs() < 1e-6);
+
+        // Written out, the vertices are back where they were in the file.
+        let mut out = Vec::new();
+        local.write_obj(&mut out, 3).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("v 1000000.011 2000000.002 3000000.003"));
+    }
+
+    #[test]
+    fn a_single_flipped_face_is_found_and_fixed() {
+        // A tetrahedron wound consistently, and the same one with its last face turned around.
+        let text = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 3 2\nf 1 2 4\nf 2 3 4\nf 1 4 3\n";
+        let consistent = parse(text);
+        assert_eq!(consistent.validate().flipped_triangles, 0);
 
-        // Return the bounding box as two points (min and max).
-        (min, max)
+        let mut model = parse(&text.replace("f 1 4 3", "f 1 3 4"));
+        assert_eq!(model.validate().flipped_triangles, 1);
+        assert_eq!(model.fix_winding(), 1);
+        assert_eq!(model.triangles, consistent.triangles);
+        let validation = model.validate();
+        assert_eq!((validation.flipped_triangles, validation.non_orientable_pieces), (0, 0));
     }
 }
@@ -0,0 +1,619 @@
+use crate::{screen, sheet, three};
+use std::*;
+
+// Lowest number of frames per second accepted for any frame rate, well above where a frame stops fitting in a Duration.
+const MIN_FRAME_RATE: f32 = 0.01;
+
+// Options collected from the command-line arguments.
+pub struct Options {
+    // Paths of the model files to visualize. Only sequences can have more than one, which can also be directories.
+    pub file_paths: Vec<String>,
+
+    // Name of a built-in demo model to visualize instead of a file.
+    pub demo: Option<String>,
+
+    // Whether to print the help or version information instead of visualizing a model.
+    pub help: bool,
+    pub version: bool,
+
+    // Whether to print the model formats this build can load instead of visualizing a model.
+    pub list_formats: bool,
+
+    // Whether to check the model for mesh problems and exit, and whether to report them as JSON.
+    pub validate: bool,
+    pub json: bool,
+
+    // When set, render a single frame in this format to stdout instead of starting the interactive session.
+    pub output_format: Option<screen::OutputFormat>,
+
+    // When set, write the loaded and transformed model to this .obj file instead of visualizing it,
+    // with coordinates rounded to this many decimal places.
+    pub export: Option<String>,
+    pub export_precision: usize,
+
+    // When set, render these named views headlessly and write them side by side to this .ppm image, labeled with their names.
+    pub contact_sheet: Option<String>,
+    pub views: Option<Vec<String>>,
+
+    // File to load the camera path from and save its keyframes to, and when set, how many frames along it to print headlessly.
+    pub camera_path: Option<String>,
+    pub path_frames: Option<u32>,
+
+    // When set, render this many frames headlessly while rotating and print timing statistics.
+    pub bench: Option<u32>,
+
+    // Longest a headless render, benchmark or export may take before giving up, in seconds.
+    pub timeout: Option<f32>,
+
+    // Size of the headless render in terminal cells.
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+
+    // Whether to move the model so its bounding box is centered on the origin.
+    pub recenter: bool,
+
+    // Whether to keep only the model's largest connected component, dropping stray geometry.
+    pub largest_component: bool,
+
+    // Whether to turn over triangles wound the other way around from their neighbors.
+    pub fix_winding: bool,
+
+    // Whether to invert the rendered pixels, for terminals with light backgrounds.
+    pub invert: bool,
+
+    // Kind of pixels to start drawing with, or None for the default.
+    pub pixel_mode: Option<screen::PixelMode>,
+
+    // Width of the lines used to draw edges, in screen pixels.
+    pub line_width: u16,
+
+    // Depths at which fog starts fading edges and fully hides them.
+    pub fog_start: Option<f32>,
+    pub fog_end: Option<f32>,
+
+    // Whether to leave the mouse uncaptured, relying on keyboard controls only.
+    pub no_mouse: bool,
+
+    // Whether to start out showing the coordinates of the vertex under the mouse.
+    pub probe: bool,
+
+    // Whether to move the origin to the center of the model while loading, keeping models far from the origin precise.
+    pub local_origin: bool,
+
+    // Whether to keep the edges of .obj files in file order with their duplicates, instead of sorting and deduplicating them.
+    pub no_dedup: bool,
+
+    // How fast dragging the mouse rotates and pans the view, relative to the default speeds.
+    pub rotate_speed: f32,
+    pub pan_speed: f32,
+
+    // Distance panned per key press, relative to the radius of the model's bounding sphere.
+    pub pan_step: f32,
+
+    // World axis (0 for x, 1 for y, 2 for z) that the section view's clip plane is perpendicular to, if enabled.
+    pub clip_axis: Option<usize>,
+
+    // Whether changing the field of view moves the camera to keep the model the same size on screen.
+    pub fov_coupling: bool,
+
+    // Initial orientation of the camera in radians, and its distance from the model.
+    pub yaw: Option<f32>,
+    pub pitch: Option<f32>,
+    pub roll: Option<f32>,
+    pub distance: Option<f32>,
+
+    // Point to place the camera at, the point for it to look at, and the direction to keep up on screen, instead of the orientation and distance.
+    pub eye: Option<(f32, f32, f32)>,
+    pub target: Option<(f32, f32, f32)>,
+    pub up: Option<(f32, f32, f32)>,
+
+    // Point for the camera to orbit around, or None for the center of the model.
+    pub rotate_about: Option<(f32, f32, f32)>,
+
+    // Whether to reload the model whenever its file changes on disk.
+    pub watch: bool,
+
+    // Whether to suppress non-fatal warnings, which are otherwise printed to stderr.
+    pub quiet: bool,
+
+    // Largest number of edges to draw at all times, and while the view is being moved.
+    pub max_edges: Option<usize>,
+    pub interactive_max_edges: Option<usize>,
+    // How the edges to draw are picked when there are more than that.
+    pub edge_priority: three::EdgePriority,
+
+    // How to write colors to the terminal, or None to detect what it supports.
+    pub color_mode: Option<screen::ColorMode>,
+
+    // Number of times to smooth the model's triangles with subdivision.
+    pub subdivide: Option<u32>,
+
+    // Whether to start with every face in its own pseudo-random color, and the seed those colors are picked with.
+    pub face_colors: bool,
+    pub seed: u64,
+
+    // Most frames drawn per second while the view is changing, and while nothing is.
+    pub max_fps: f32,
+    pub max_fps_idle: f32,
+
+    // Whether to play the files back as a sequence, and how many of them to show per second.
+    pub sequence: bool,
+    pub fps: f32,
+
+    // Whether the model was made with the Z axis pointing up, rather than the Y axis as this viewer expects.
+    pub z_up: bool,
+
+    // World axis (0 for x, 1 for y, 2 for z) to mirror the model on when loading it, if any.
+    pub flip: Option<usize>,
+
+    // How far to push the model's groups apart for an exploded view, with 0 leaving it assembled.
+    pub explode: f32,
+
+    // Brightness (0 to 1) added to every shaded face, and the directions towards the lights with their intensities, or none to use the default lights.
+    pub ambient: f32,
+    pub lights: Vec<((f32, f32, f32), f32)>,
+}
+
+impl Options {
+    // Parses the command-line arguments on top of default options separated by whitespace, such as from an environment variable.
+    // The defaults are parsed first, so that the same options given on the command line override them.
+    pub fn parse_with_defaults(defaults: &str, args: &[String]) -> Result<Options, String> {
+        let mut combined: Vec<String> = args.iter().take(1).cloned().collect();
+        combined.extend(defaults.split_whitespace().map(String::from));
+        combined.extend(args.iter().skip(1).cloned());
+
+        let mut options = Options::parse(&combined).map_err(|error| match defaults.trim() {
+            "" => error,
+            defaults => format!("{} The default options were \"{}\".", error, defaults),
+        })?;
+        options.help |= args.len() <= 1;  // Default options alone still show help, as there is no model to view.
+        Ok(options)
+    }
+
+    // Parses the command-line arguments, skipping the program name in the first position.
+    pub fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options {
+            file_paths: Vec::new(),
+            demo: None,
+            help: args.len() <= 1,  // Show help when no arguments are given.
+            version: false,
+            list_formats: false,
+            validate: false,
+            json: false,
+            output_format: None,
+            export: None,
+            export_precision: 6,
+            contact_sheet: None,
+            views: None,
+            camera_path: None,
+            path_frames: None,
+            bench: None,
+            timeout: None,
+            width: None,
+            height: None,
+            recenter: false,
+            largest_component: false,
+            fix_winding: false,
+            invert: false,
+            pixel_mode: None,
+            line_width: 1,
+            fog_start: None,
+            fog_end: None,
+            no_mouse: false,
+            no_dedup: false,
+            local_origin: false,
+            probe: false,
+            rotate_speed: 1.,
+            pan_speed: 1.,
+            pan_step: 0.1,
+            clip_axis: None,
+            fov_coupling: true,
+            yaw: None,
+            pitch: None,
+            roll: None,
+            distance: None,
+            eye: None,
+            target: None,
+            up: None,
+            rotate_about: None,
+            watch: false,
+            quiet: false,
+            max_edges: None,
+            interactive_max_edges: None,
+            edge_priority: three::EdgePriority::Silhouette,
+            color_mode: None,
+            subdivide: None,
+            face_colors: false,
+            seed: 0,
+            max_fps: 60.,
+            max_fps_idle: 5.,
+            sequence: false,
+            fps: 10.,
+            z_up: false,
+            flip: None,
+            explode: 0.,
+            ambient: 0.,
+            lights: Vec::new(),
+        };
+
+        let mut args = args.iter().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "-help" | "--h" | "--help" => options.help = true,
+                "-v" | "-version" | "--v" | "--version" => options.version = true,
+                "--list-formats" => options.list_formats = true,
+                "--validate" => options.validate = true,
+                "--json" => options.json = true,
+                "--demo" => options.demo = Some(String::from(value(arg, args.next())?)),
+                "--output-format" => options.output_format = Some(parse_output_format(value(arg, args.next())?)?),
+                "--export" => options.export = Some(String::from(value(arg, args.next())?)),
+                "--export-precision" => options.export_precision = parse_value(arg, args.next())?,
+                "--timeout" => {
+                    let timeout: f32 = parse_value(arg, args.next())?;
+                    if !(timeout.is_finite() && timeout > 0.) {
+                        return Err(String::from("\"--timeout\" must be a positive number of seconds."));
+                    }
+                    options.timeout = Some(timeout);
+                }
+                "--contact-sheet" => options.contact_sheet = Some(String::from(value(arg, args.next())?)),
+                "--views" => options.views = Some(parse_views(value(arg, args.next())?)?),
+                "--camera-path" => options.camera_path = Some(String::from(value(arg, args.next())?)),
+                "--path-frames" => options.path_frames = Some(parse_value(arg, args.next())?),
+                "--bench" => options.bench = Some(parse_value(arg, args.next())?),
+                "--width" => options.width = Some(parse_cells(arg, args.next(), screen::LARGEST_CELL_SIZE.0)?),
+                "--height" => options.height = Some(parse_cells(arg, args.next(), screen::LARGEST_CELL_SIZE.1)?),
+                "--recenter" => options.recenter = true,
+                "--largest-component" => options.largest_component = true,
+                "--fix-winding" => options.fix_winding = true,
+                "--invert" => options.invert = true,
+                "--line-width" => options.line_width = parse_value(arg, args.next())?,
+                "--fog-start" => options.fog_start = Some(parse_value(arg, args.next())?),
+                "--fog-end" => options.fog_end = Some(parse_value(arg, args.next())?),
+                "--no-mouse" => options.no_mouse = true,
+                "--no-dedup" => options.no_dedup = true,
+                "--local-origin" => options.local_origin = true,
+                "--probe" => options.probe = true,
+                "--rotate-speed" | "--pan-speed" => {
+                    let speed: f32 = parse_value(arg, args.next())?;
+                    if !(speed.is_finite() && speed > 0.) {
+                        return Err(format!("\"{}\" must be a positive number.", arg));
+                    }
+                    if arg == "--rotate-speed" { options.rotate_speed = speed } else { options.pan_speed = speed }
+                }
+                "--pan-step" => {
+                    let step: f32 = parse_value(arg, args.next())?;
+                    if !(step.is_finite() && step > 0.) {
+                        return Err(String::from("\"--pan-step\" must be a positive number."));
+                    }
+                    options.pan_step = step;
+                }
+                "--yaw" => options.yaw = Some(parse_angle(arg, value(arg, args.next())?)?),
+                "--pitch" => options.pitch = Some(parse_angle(arg, value(arg, args.next())?)?),
+                "--roll" => options.roll = Some(parse_angle(arg, value(arg, args.next())?)?),
+                "--distance" => {
+                    let distance: f32 = parse_value(arg, args.next())?;
+                    if !(distance.is_finite() && distance > 0.) {
+                        return Err(String::from("\"--distance\" must be a positive number."));
+                    }
+                    options.distance = Some(distance);
+                }
+                "--eye" => options.eye = Some(parse_point(arg, value(arg, args.next())?)?),
+                "--target" => options.target = Some(parse_point(arg, value(arg, args.next())?)?),
+                "--up" => options.up = Some(parse_direction(arg, value(arg, args.next())?)?),
+                "--rotate-about" => options.rotate_about = parse_rotate_about(arg, value(arg, args.next())?)?,
+                "--watch" => options.watch = true,
+                "--quiet" => options.quiet = true,
+                "--sequence" => options.sequence = true,
+                "--max-fps" => options.max_fps = parse_frame_rate(arg, args.next())?,
+                "--max-fps-idle" => options.max_fps_idle = parse_frame_rate(arg, args.next())?,
+                "--fps" => options.fps = parse_frame_rate(arg, args.next())?,
+                "--explode" => {
+                    let explode: f32 = parse_value(arg, args.next())?;
+                    if !(explode.is_finite() && explode >= 0.) {
+                        return Err(String::from("\"--explode\" must be a number that isn't negative."));
+                    }
+                    options.explode = explode;
+                }
+                "--ambient" => {
+                    let ambient: f32 = parse_value(arg, args.next())?;
+                    if !(0. ..=1.).contains(&ambient) {
+                        return Err(String::from("\"--ambient\" must be between 0 and 1."));
+                    }
+                    options.ambient = ambient;
+                }
+                "--light" => options.lights.push(parse_light(arg, value(arg, args.next())?)?),
+                "--light-dir" => options.lights.push((parse_direction(arg, value(arg, args.next())?)?, 1.)),
+                "--face-colors" => options.face_colors = true,
+                "--seed" => options.seed = parse_value(arg, args.next())?,
+                "--subdivide" => options.subdivide = Some(parse_value(arg, args.next())?),
+                "--no-color" => options.color_mode = Some(screen::ColorMode::Monochrome),
+                "--color-mode" => options.color_mode = Some(parse_color_mode(value(arg, args.next())?)?),
+                "--max-edges" => options.max_edges = Some(parse_value(arg, args.next())?),
+                "--interactive-max-edges" => options.interactive_max_edges = Some(parse_value(arg, args.next())?),
+                "--edge-priority" => options.edge_priority = parse_edge_priority(value(arg, args.next())?)?,
+                "--axis" => options.z_up = parse_axis_convention(value(arg, args.next())?)?,
+                "--no-fov-coupling" => options.fov_coupling = false,
+                "--pixel" => options.pixel_mode = Some(parse_pixel_mode(value(arg, args.next())?)?),
+                "--flip" => options.flip = Some(parse_axis(value(arg, args.next())?)?),
+                "--clip-plane" => options.clip_axis = Some(parse_axis(value(arg, args.next())?)?),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option \"{}\".", arg)),
+                _ => options.file_paths.push(arg.clone()),
+            }
+        }
+
+        if options.file_paths.len() > 1 && !options.sequence {
+            return Err(String::from("Please supply only one file path to visualize, or use \"--sequence\"."));
+        }
+
+        let headless = options.output_format.is_some() || options.bench.is_some() || options.contact_sheet.is_some() || options.path_frames.is_some();
+        if options.timeout.is_some() && !headless && options.export.is_none() {
+            return Err(String::from(
+                "\"--timeout\" requires \"--output-format\", \"--bench\", \"--contact-sheet\", \"--path-frames\" or \"--export\".",
+            ));
+        }
+
+        if options.path_frames.is_some() && options.camera_path.is_none() {
+            return Err(String::from("\"--path-frames\" requires \"--camera-path\"."));
+        }
+
+        if options.views.is_some() && options.contact_sheet.is_none() {
+            return Err(String::from("\"--views\" requires \"--contact-sheet\"."));
+        }
+
+        if options.fog_start.is_some() && options.fog_end.is_none() {
+            return Err(String::from("\"--fog-start\" requires \"--fog-end\"."));
+        }
+
+        Ok(options)
+    }
+}
+
+// Returns the value following a flag, or an error if the flag was the last argument.
+fn value<'a>(flag: &str, value: Option<&'a String>) -> Result<&'a str, String> {
+    match value {
+        Some(value) => Ok(value),
+        None => Err(format!("Missing value for \"{}\".", flag)),
+    }
+}
+
+// Parses the value following a flag into the requested type.
+fn parse_value<T: str::FromStr>(flag: &str, next: Option<&String>) -> Result<T, String> {
+    let text = value(flag, next)?;
+    text.parse::<T>().map_err(|_| format!("Invalid value \"{}\" for \"{}\".", text, flag))
+}
+
+// Parses a number of terminal cells, which must be at least one and few enough that their pixels fit in a screen's dimensions
+// with a given number of pixels per cell.
+fn parse_cells(flag: &str, next: Option<&String>, cell_size: u16) -> Result<u16, String> {
+    let cells: u16 = parse_value(flag, next)?;
+    if cells == 0 || cells.checked_mul(cell_size).is_none() {
+        return Err(format!("\"{}\" must be between 1 and {}.", flag, u16::MAX / cell_size));
+    }
+    Ok(cells)
+}
+
+// Parses a number of frames per second. Rates below MIN_FRAME_RATE are refused, as their frames would take too long to time.
+fn parse_frame_rate(flag: &str, next: Option<&String>) -> Result<f32, String> {
+    let fps: f32 = parse_value(flag, next)?;
+    if !(fps.is_finite() && fps >= MIN_FRAME_RATE) {
+        return Err(format!("\"{}\" must be a number of frames per second of at least {}.", flag, MIN_FRAME_RATE));
+    }
+    Ok(fps)
+}
+
+// Parses the name of a world axis into its index.
+fn parse_axis(name: &str) -> Result<usize, String> {
+    match name {
+        "x" => Ok(0),
+        "y" => Ok(1),
+        "z" => Ok(2),
+        _ => Err(format!("Unknown axis \"{}\", expected x, y or z.", name)),
+    }
+}
+
+// Parses the name of an up-axis convention, returning whether it is Z-up.
+fn parse_axis_convention(name: &str) -> Result<bool, String> {
+    match name {
+        "y-up" => Ok(false),
+        "z-up" => Ok(true),
+        _ => Err(format!("Unknown axis convention \"{}\", expected y-up or z-up.", name)),
+    }
+}
+
+// Parses an output format from its command-line name.
+fn parse_output_format(name: &str) -> Result<screen::OutputFormat, String> {
+    match name {
+        "plain" => Ok(screen::OutputFormat::Plain),
+        "ansi" => Ok(screen::OutputFormat::Ansi),
+        "html" => Ok(screen::OutputFormat::Html),
+        _ => Err(format!("Unknown output format \"{}\", expected plain, ansi or html.", name)),
+    }
+}
+
+// Parses the name of a kind of pixels.
+fn parse_pixel_mode(name: &str) -> Result<screen::PixelMode, String> {
+    match name {
+        "block" => Ok(screen::PixelMode::Block),
+        "braille" => Ok(screen::PixelMode::Braille),
+        "sextant" => Ok(screen::PixelMode::Sextant),
+        "ascii" => Ok(screen::PixelMode::Ascii),
+        _ => Err(format!("Unknown pixel type \"{}\", expected block, braille, sextant or ascii.", name)),
+    }
+}
+
+// Parses a comma-separated list of named views, such as "front,top,iso".
+fn parse_views(text: &str) -> Result<Vec<String>, String> {
+    text.split(',').map(|name| {
+        let name = name.trim();
+        match sheet::view_angles(name) {
+            Some(_) => Ok(String::from(name)),
+            None => {
+                let names: Vec<&str> = sheet::VIEWS.iter().map(|(view, _, _)| *view).collect();
+                Err(format!("Unknown view \"{}\", expected {}.", name, names.join(", ")))
+            }
+        }
+    }).collect()
+}
+
+// Parses the name of a way to pick edges.
+fn parse_edge_priority(name: &str) -> Result<three::EdgePriority, String> {
+    match name {
+        "spread" => Ok(three::EdgePriority::Spread),
+        "length" => Ok(three::EdgePriority::Length),
+        "silhouette" => Ok(three::EdgePriority::Silhouette),
+        _ => Err(format!("Unknown edge priority \"{}\", expected spread, length or silhouette.", name)),
+    }
+}
+
+// Parses the name of a color mode.
+fn parse_color_mode(name: &str) -> Result<screen::ColorMode, String> {
+    match name {
+        "truecolor" => Ok(screen::ColorMode::TrueColor),
+        "256" => Ok(screen::ColorMode::Palette256),
+        "none" => Ok(screen::ColorMode::Monochrome),
+        _ => Err(format!("Unknown color mode \"{}\", expected truecolor, 256 or none.", name)),
+    }
+}
+
+// Parses a direction given as comma-separated x, y and z components, which can't all be zero.
+fn parse_direction(flag: &str, text: &str) -> Result<(f32, f32, f32), String> {
+    match parse_point(flag, text) {
+        Ok(direction) if direction != (0., 0., 0.) => Ok(direction),
+        _ => Err(format!("Invalid direction \"{}\" for \"{}\", expected x,y,z.", text, flag)),
+    }
+}
+
+// Parses a point given as its comma-separated coordinates, such as "1,2.5,-3".
+fn parse_point(flag: &str, text: &str) -> Result<(f32, f32, f32), String> {
+    let components: Vec<f32> = text
+        .split(',')
+        .map(|component| component.trim().parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid point \"{}\" for \"{}\", expected x,y,z.", text, flag))?;
+
+    match components.as_slice() {
+        &[x, y, z] if components.iter().all(|component| component.is_finite()) => Ok((x, y, z)),
+        _ => Err(format!("Invalid point \"{}\" for \"{}\", expected x,y,z.", text, flag)),
+    }
+}
+
+// Parses the point to orbit around, either "origin", "center" (the center of the model, given as None) or a point.
+fn parse_rotate_about(flag: &str, text: &str) -> Result<Option<(f32, f32, f32)>, String> {
+    match text {
+        "origin" => Ok(Some((0., 0., 0.))),
+        "center" => Ok(None),
+        _ => parse_point(flag, text).map(Some)
+            .map_err(|_| format!("Invalid point \"{}\" for \"{}\", expected origin, center or x,y,z.", text, flag)),
+    }
+}
+
+// Parses a light given as "dir:" followed by its direction, and optionally its intensity from 0 to 1 (1 by default), such as "dir:1,1,0,0.5".
+fn parse_light(flag: &str, text: &str) -> Result<((f32, f32, f32), f32), String> {
+    let invalid = || format!("Invalid light \"{}\" for \"{}\", expected dir:x,y,z or dir:x,y,z,intensity.", text, flag);
+    let components: Vec<&str> = text.strip_prefix("dir:").ok_or_else(invalid)?.split(',').collect();
+    let (direction, intensity) = match components.len() {
+        3 => (components.join(","), 1.),
+        4 => (components[..3].join(","), components[3].trim().parse::<f32>().map_err(|_| invalid())?),
+        _ => return Err(invalid()),
+    };
+    if !(0. ..=1.).contains(&intensity) {
+        return Err(format!("The intensity of a light for \"{}\" must be between 0 and 1.", flag));
+    }
+    Ok((parse_direction(flag, &direction)?, intensity))
+}
+
+// Parses an angle into radians, given either in radians or in degrees with a "deg" or "°" suffix.
+fn parse_angle(flag: &str, text: &str) -> Result<f32, String> {
+    let (number, in_degrees) = match text.strip_suffix("deg").or_else(|| text.strip_suffix('°')) {
+        Some(number) => (number, true),
+        None => (text.strip_suffix("rad").unwrap_or(text), false),
+    };
+
+    match number.parse::<f32>() {
+        Ok(angle) if angle.is_finite() => Ok(if in_degrees { angle.to_radians() } else { angle }),
+        _ => Err(format!("Invalid angle \"{}\" for \"{}\".", text, flag)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Splits a command line into arguments, after the program name.
+    fn args(line: &str) -> Vec<String> {
+        iter::once("termite").chain(line.split_whitespace()).map(String::from).collect()
+    }
+
+    #[test]
+    fn frame_sizes_must_fit_in_a_screen() {
+        let options = Options::parse(&args("--width 120 --height 40 model.obj")).unwrap();
+        assert_eq!((options.width, options.height), (Some(120), Some(40)));
+        for line in ["--width 0 model.obj", "--width 40000 model.obj", "--height 20000 model.obj", "--height -1 model.obj"] {
+            assert!(Options::parse(&args(line)).is_err());
+        }
+    }
+
+    #[test]
+    fn angles_are_read_in_radians_or_degrees() {
+        assert!((parse_angle("--yaw", "90deg").unwrap() - f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert!((parse_angle("--yaw", "-45°").unwrap() + f32::consts::FRAC_PI_4).abs() < 1e-6);
+        assert_eq!(parse_angle("--yaw", "1.5rad"), Ok(1.5));
+        assert_eq!(parse_angle("--yaw", "1.5"), Ok(1.5));
+        for text in ["", "deg", "90 degrees", "infdeg", "NaN"] {
+            assert_eq!(parse_angle("--yaw", text), Err(format!("Invalid angle \"{}\" for \"--yaw\".", text)));
+        }
+    }
+
+    #[test]
+    fn light_directions_are_three_components_not_all_zero() {
+        assert_eq!(parse_direction("--light-dir", "1, -2, 0.5"), Ok((1., -2., 0.5)));
+        for text in ["0,0,0", "1,2", "1,2,3,4", "1,x,3"] {
+            assert!(parse_direction("--light-dir", text).is_err());
+        }
+    }
+
+    #[test]
+    fn command_line_options_override_the_defaults() {
+        let options = Options::parse_with_defaults("--yaw 10deg --invert", &args("--yaw 20deg model.obj")).unwrap();
+        assert!((options.yaw.unwrap() - 20_f32.to_radians()).abs() < 1e-6);
+        assert!(options.invert);
+        assert_eq!(options.file_paths, vec![String::from("model.obj")]);
+
+        // Default options alone still show the help, and errors in them say where they came from.
+        assert!(Options::parse_with_defaults("--invert", &args("")).unwrap().help);
+        let error = Options::parse_with_defaults("--bogus", &args("model.obj")).err().unwrap();
+        assert!(error.ends_with("The default options were \"--bogus\"."));
+        assert!(Options::parse_with_defaults("", &args("--bogus model.obj")).err().unwrap().starts_with("Unknown option"));
+    }
+
+    #[test]
+    fn lights_take_a_direction_and_an_optional_intensity() {
+        assert_eq!(parse_light("--light", "dir:1,1,0"), Ok(((1., 1., 0.), 1.)));
+        assert_eq!(parse_light("--light", "dir:0,-1,0,0.25"), Ok(((0., -1., 0.), 0.25)));
+        for text in ["1,1,0", "dir:", "dir:1,1", "dir:0,0,0", "dir:1,1,0,2", "dir:1,1,0,half"] {
+            assert!(parse_light("--light", text).is_err());
+        }
+
+        let options = Options::parse(&args("--light dir:1,0,0,0.5 --light-dir 0,1,0 model.obj")).unwrap();
+        assert_eq!(options.lights, vec![((1., 0., 0.), 0.5), ((0., 1., 0.), 1.)]);
+    }
+
+    #[test]
+    fn the_camera_can_be_placed_by_its_eye_target_and_up() {
+        let options = Options::parse(&args("--eye 1,2.5,-3 --target 0,0,0 --up 0,0,1 model.obj")).unwrap();
+        assert_eq!((options.eye, options.target, options.up), (Some((1., 2.5, -3.)), Some((0., 0., 0.)), Some((0., 0., 1.))));
+        for text in ["1,2", "1,2,3,4", "1,2,inf", "a,b,c"] {
+            assert_eq!(parse_point("--eye", text), Err(format!("Invalid point \"{}\" for \"--eye\", expected x,y,z.", text)));
+        }
+        assert!(Options::parse(&args("--up 0,0,0 model.obj")).is_err());
+    }
+
+    #[test]
+    fn frame_rates_below_the_minimum_are_refused() {
+        let options = Options::parse(&args("--max-fps 30 --max-fps-idle 0.01 model.obj")).unwrap();
+        assert_eq!((options.max_fps, options.max_fps_idle), (30., 0.01));
+        for line in ["--fps 0 model.obj", "--max-fps 0.001 model.obj", "--max-fps-idle inf model.obj", "--max-fps -5 model.obj"] {
+            assert!(Options::parse(&args(line)).is_err());
+        }
+    }
+}
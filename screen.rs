@@ -1,13 +1,130 @@
 use std::*;
+use io::{IsTerminal, Write};
 use crossterm::{
     execute, 
+    queue,
     terminal,
     cursor,
     style
 };
 
 // Default terminal dimensions in case querying the terminal size fails.
-const DEFAULT_TERMINAL_DIMENSIONS: (u16, u16) = (80, 24);
+pub const DEFAULT_TERMINAL_DIMENSIONS: (u16, u16) = (80, 24);
+
+// Smallest usable area (in terminal cells) to render into; smaller terminals show a message instead.
+const MINIMUM_TERMINAL_DIMENSIONS: (u16, u16) = (20, 5);
+
+// Most pixels any pixel type packs into a terminal cell across and down, which is the braille pixel's 2x4 grid.
+pub const LARGEST_CELL_SIZE: (u16, u16) = (2, 4);
+
+// Message shown in place of the model when the terminal is smaller than the minimum dimensions.
+const TOO_SMALL_MSG: &str = "Terminal too small";
+
+// 4x4 Bayer matrix used as the threshold map for ordered dithering.
+const BAYER_MATRIX: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+// Text formats that a screen's content can be written out as, for headless rendering.
+#[derive(Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    Plain,  // Only the glyphs, exactly as the terminal shows them.
+    Ansi,  // Glyphs with ANSI escape sequences for styling.
+    Html,  // A <pre> block suitable for embedding in web pages.
+}
+
+// How text stamped onto a screen's overlay layer with blit_text looks.
+#[derive(Copy, Clone, PartialEq, Default)]
+pub struct OverlayStyle {
+    pub foreground: Option<style::Color>,  // Color of the text, or None for the terminal's default color.
+    pub background: Option<style::Color>,  // Color behind the text, or None for the terminal's default background.
+    pub reverse: bool,  // Whether to swap the text and background colors, which stands out even without colors.
+}
+
+impl OverlayStyle {
+    // Returns the escape sequences switching the terminal to this style from any other, with colors written in a color mode.
+    fn escapes(self, mode: ColorMode) -> String {
+        let mut text = style::SetAttribute(style::Attribute::Reset).to_string();
+        if self.reverse {
+            text.push_str(&style::SetAttribute(style::Attribute::Reverse).to_string());
+        }
+        if let Some(color) = self.foreground.and_then(|color| apply_color(color, mode)) {
+            text.push_str(&style::SetForegroundColor(color).to_string());
+        }
+        if let Some(color) = self.background.and_then(|color| apply_color(color, mode)) {
+            text.push_str(&style::SetBackgroundColor(color).to_string());
+        }
+        text
+    }
+}
+
+// Levels of each component in the 6x6x6 color cube of the 256-color palette, which starts at index 16.
+const PALETTE_CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// How colors are written to the terminal.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ColorMode {
+    TrueColor,  // 24-bit colors, written as they are.
+    Palette256,  // The 256-color palette, with 24-bit colors replaced by the nearest palette color.
+    Monochrome,  // No colors, only the terminal's default color.
+}
+
+impl ColorMode {
+    // Guesses what the terminal supports from the environment, honoring the NO_COLOR convention.
+    pub fn detect() -> ColorMode {
+        if env::var_os("NO_COLOR").is_some() {
+            return ColorMode::Monochrome;
+        }
+        if let Ok("truecolor" | "24bit") = env::var("COLORTERM").as_deref() {
+            return ColorMode::TrueColor;
+        }
+        match env::var("TERM").as_deref() {
+            Ok("dumb") => ColorMode::Monochrome,
+            _ => ColorMode::Palette256,  // Nearly every terminal in use supports at least 256 colors.
+        }
+    }
+}
+
+// Returns the color to write for a pixel's color in a color mode, or None if it should use the terminal's default color.
+// All color output goes through here, so every colored mode degrades the same way on limited terminals.
+pub fn apply_color(color: style::Color, mode: ColorMode) -> Option<style::Color> {
+    match (mode, color) {
+        (ColorMode::Monochrome, _) => None,
+        (ColorMode::Palette256, style::Color::Rgb { r, g, b }) => Some(style::Color::AnsiValue(nearest_palette_color(r, g, b))),
+        _ => Some(color),
+    }
+}
+
+// Finds the closest color in the 256-color palette, from either the color cube or the grayscale ramp.
+fn nearest_palette_color(r: u8, g: u8, b: u8) -> u8 {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        (r as i32 - r2 as i32).pow(2) + (g as i32 - g2 as i32).pow(2) + (b as i32 - b2 as i32).pow(2)
+    };
+
+    // Pick the nearest level of the cube for each component separately.
+    let nearest_level = |component: u8| {
+        (0..PALETTE_CUBE_LEVELS.len())
+            .min_by_key(|&level| (PALETTE_CUBE_LEVELS[level] as i32 - component as i32).abs())
+            .unwrap()
+    };
+    let (cube_r, cube_g, cube_b) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube_index = 16 + 36 * cube_r + 6 * cube_g + cube_b;
+    let cube_color = (PALETTE_CUBE_LEVELS[cube_r], PALETTE_CUBE_LEVELS[cube_g], PALETTE_CUBE_LEVELS[cube_b]);
+
+    // The grayscale ramp has 24 shades from 8 to 238 in steps of 10, starting at index 232.
+    let average = (r as i32 + g as i32 + b as i32) / 3;
+    let gray_step = ((average - 8 + 5) / 10).clamp(0, 23);
+    let gray = (8 + 10 * gray_step) as u8;
+
+    if distance((gray, gray, gray)) < distance(cube_color) {
+        232 + gray_step as u8
+    } else {
+        cube_index as u8
+    }
+}
 
 // A trait for objects that have defined width and height dimensions.
 pub trait Dim {
@@ -86,6 +203,177 @@ impl Pixel for BrailePixel {
     }
 }
 
+// A SextantPixel is a 3x2 grid represented by the block sextant characters, which need a font supporting Unicode 13.
+pub type SextantPixel = [[bool; 2]; 3];
+impl Pixel for SextantPixel {
+    // Creates a new SextantPixel with all values set to false (off).
+    fn new() -> SextantPixel { [[false; SextantPixel::WIDTH]; SextantPixel::HEIGHT] }
+
+    // Converts the 3x2 grid of booleans into a block sextant character, numbering the cells row by row from the top left.
+    fn to_char(&self) -> char {
+        let mut sextant: u32 = 0;
+        for (row, cells) in self.iter().enumerate() {
+            for (column, &cell) in cells.iter().enumerate() {
+                if cell { sextant |= 1 << (row * SextantPixel::WIDTH + column) }
+            }
+        }
+
+        // The sextant block skips the patterns that already exist as other block characters.
+        match sextant {
+            0 => ' ',
+            0b010101 => '▌',
+            0b101010 => '▐',
+            0b111111 => '█',
+            _ => {
+                let skipped = (sextant > 0b010101) as u32 + (sextant > 0b101010) as u32;
+                char::from_u32(0x1FB00 + sextant - 1 - skipped).unwrap()
+            }
+        }
+    }
+}
+
+// An AsciiPixel is a 2x2 grid drawn with plain ASCII characters, for fonts without any block or Braille characters.
+// It wraps the grid so that it is a different type from BlockPixel, which has the same dimensions.
+#[derive(Clone)]
+pub struct AsciiPixel([[bool; 2]; 2]);
+
+impl Dim for AsciiPixel {
+    const WIDTH: usize = 2;
+    const HEIGHT: usize = 2;
+}
+
+impl ops::Index<usize> for AsciiPixel {
+    type Output = [bool; 2];
+
+    fn index(&self, row: usize) -> &[bool; 2] {
+        &self.0[row]
+    }
+}
+
+impl ops::IndexMut<usize> for AsciiPixel {
+    fn index_mut(&mut self, row: usize) -> &mut [bool; 2] {
+        &mut self.0[row]
+    }
+}
+
+impl Pixel for AsciiPixel {
+    // Creates a new AsciiPixel with all values set to false (off).
+    fn new() -> AsciiPixel { AsciiPixel([[false; AsciiPixel::WIDTH]; AsciiPixel::HEIGHT]) }
+
+    // Converts the 2x2 grid of booleans into the ASCII character whose shape is closest.
+    fn to_char(&self) -> char {
+        match self.0 {
+            [[false, false], [false, false]] => ' ',
+            [[true, false], [false, false]] => '`',
+            [[false, true], [false, false]] => '\'',
+            [[true, true], [false, false]] => '"',
+            [[false, false], [true, false]] => ',',
+            [[true, false], [true, false]] => '|',
+            [[false, true], [true, false]] => '/',
+            [[true, true], [true, false]] => 'p',
+            [[false, false], [false, true]] => '.',
+            [[true, false], [false, true]] => '\\',
+            [[false, true], [false, true]] => '|',
+            [[true, true], [false, true]] => 'q',
+            [[false, false], [true, true]] => '_',
+            [[true, false], [true, true]] => 'b',
+            [[false, true], [true, true]] => 'd',
+            [[true, true], [true, true]] => '#'
+        }
+    }
+}
+
+// The kinds of pixels a screen can be drawn with, which trade resolution for the characters the terminal's font needs.
+#[derive(Copy, Clone, PartialEq)]
+pub enum PixelMode {
+    Block,  // BlockPixel.
+    Braille,  // BrailePixel.
+    Sextant,  // SextantPixel.
+    Ascii,  // AsciiPixel.
+}
+
+impl PixelMode {
+    // Guesses from the environment whether the terminal can show Braille characters, falling back to ASCII when it likely can't.
+    // Fonts can't be asked which characters they have, so this only catches terminals without Unicode: a locale that isn't UTF-8,
+    // as on many servers reached over SSH, or a terminal such as the Linux console or a VT100 whose fonts lack Braille.
+    pub fn detect() -> PixelMode {
+        if cfg!(windows) {
+            return PixelMode::Braille;  // Windows consoles take Unicode regardless of the (usually unset) locale variables.
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let unicode_locale = locale.contains("utf-8") || locale.contains("utf8");
+        let plain_terminal = match env::var("TERM") {
+            Ok(term) => term.is_empty() || term == "dumb" || term == "linux" || term.starts_with("vt"),
+            Err(_) => true,
+        };
+        if unicode_locale && !plain_terminal { PixelMode::Braille } else { PixelMode::Ascii }
+    }
+
+    // Returns the name of the mode, as used on the command line and shown in the status line.
+    pub fn name(self) -> &'static str {
+        match self {
+            PixelMode::Block => "block",
+            PixelMode::Braille => "braille",
+            PixelMode::Sextant => "sextant",
+            PixelMode::Ascii => "ascii",
+        }
+    }
+
+    // Returns the number of screen pixels across and down each terminal cell in this mode.
+    pub fn cell_size(self) -> (usize, usize) {
+        match self {
+            PixelMode::Block => (BlockPixel::WIDTH, BlockPixel::HEIGHT),
+            PixelMode::Braille => (BrailePixel::WIDTH, BrailePixel::HEIGHT),
+            PixelMode::Sextant => (SextantPixel::WIDTH, SextantPixel::HEIGHT),
+            PixelMode::Ascii => (AsciiPixel::WIDTH, AsciiPixel::HEIGHT),
+        }
+    }
+
+    // Returns the mode that follows this one when cycling through them.
+    pub fn next(self) -> PixelMode {
+        match self {
+            PixelMode::Braille => PixelMode::Block,
+            PixelMode::Block => PixelMode::Sextant,
+            PixelMode::Sextant => PixelMode::Ascii,
+            PixelMode::Ascii => PixelMode::Braille,
+        }
+    }
+}
+
+// Returns the red, green and blue components of a terminal color, approximating the named colors with common terminal palettes.
+// Colors without fixed components, such as the terminal's default color, have none.
+pub fn color_components(color: style::Color) -> Option<[u8; 3]> {
+    match color {
+        style::Color::Rgb { r, g, b } => Some([r, g, b]),
+        style::Color::Black => Some([0, 0, 0]),
+        style::Color::DarkGrey => Some([128, 128, 128]),
+        style::Color::Red => Some([255, 0, 0]),
+        style::Color::DarkRed => Some([128, 0, 0]),
+        style::Color::Green => Some([0, 255, 0]),
+        style::Color::DarkGreen => Some([0, 128, 0]),
+        style::Color::Yellow => Some([255, 255, 0]),
+        style::Color::DarkYellow => Some([128, 128, 0]),
+        style::Color::Blue => Some([0, 0, 255]),
+        style::Color::DarkBlue => Some([0, 0, 128]),
+        style::Color::Magenta => Some([255, 0, 255]),
+        style::Color::DarkMagenta => Some([128, 0, 128]),
+        style::Color::Cyan => Some([0, 255, 255]),
+        style::Color::DarkCyan => Some([0, 128, 128]),
+        style::Color::Grey => Some([192, 192, 192]),
+        style::Color::White => Some([255, 255, 255]),
+        _ => None,
+    }
+}
+
+// Returns the ordered dithering threshold (0 to 1) for a pixel, which an intensity must exceed for the pixel to be set.
+fn dither_threshold(point: &Point) -> f32 {
+    (BAYER_MATRIX[point.y.rem_euclid(4) as usize][point.x.rem_euclid(4) as usize] as f32 + 0.5) / 16.
+}
+
 // A simple 2D point structure for x and y coordinates.
 #[derive(Copy, Clone)]
 pub struct Point {
@@ -98,87 +386,303 @@ impl Point {
     pub fn new(x: i32, y: i32) -> Point {
         Point { x, y }
     }
+
+    // Returns the pixel nearest to a fraction (0 to 1) of the way along a line between exact (unrounded) pixel positions.
+    pub fn along(start: (f64, f64), end: (f64, f64), fraction: f64) -> Point {
+        Point::new(
+            (start.0 + fraction * (end.0 - start.0)).round() as i32,
+            (start.1 + fraction * (end.1 - start.1)).round() as i32,
+        )
+    }
 }
 
 // A struct to represent the screen with pixel data.
 pub struct Screen {
     pub width: u16,
     pub height: u16,
+    pub inverted: bool, // Whether to draw set pixels as blank and blank pixels as set, for light terminals.
+    pub pen: Option<style::Color>, // Color given to pixels as they are set, or None for the terminal's default color.
+    pub color_mode: ColorMode, // How the colors of pixels are written to the terminal.
+    pub reserved_columns: u16, // Columns on the right of the terminal left for other things, such as a sidebar, when fitting to the terminal.
     content: Vec<Vec<bool>>, // The screen's pixel content as a 2D array of booleans.
+    colors: Vec<Vec<Option<style::Color>>>, // The color each set pixel was drawn with, matching the content.
+    depth: Vec<Vec<f32>>, // Depth of the nearest thing drawn at each pixel, or infinity where nothing was, for hiding what is behind it.
+    pub pixels_written: usize, // Number of times a pixel was set since the screen was last cleared, counting pixels set more than once each time.
+    overlay: Vec<Vec<Option<(char, OverlayStyle)>>>, // Text drawn over the pixels by terminal cell, kept apart so that either can change alone.
 }
 
 impl Screen {
-    // Constructor to create a new screen, clearing the terminal and resetting the cursor to (0,0).
+    // Constructor to create a new, empty screen.
     pub fn new() -> Screen {
-        execute!(
-            io::stdout(),
-            cursor::MoveTo(0, 0),
-            terminal::Clear(terminal::ClearType::All)
-        ).unwrap();
-
         Screen{
             content: Vec::new(),
+            colors: Vec::new(),
+            depth: Vec::new(),
+            pixels_written: 0,
+            overlay: Vec::new(),
             width: 0,
-            height: 0
+            height: 0,
+            inverted: false,
+            pen: None,
+            color_mode: ColorMode::TrueColor,
+            reserved_columns: 0,
         }
     }
 
     // Resize the screen to fit the terminal size and a specified pixel type (e.g., BlockPixel).
-    pub fn fit_to_terminal<T: Pixel>(&mut self) {
+    // Returns false if the terminal is too small to render in, in which case the screen is kept at the minimum size.
+    pub fn fit_to_terminal<T: Pixel>(&mut self) -> bool {
         let (terminal_width, terminal_height) = match terminal::size() {
             Ok(dim) => dim,
             Err(_) => DEFAULT_TERMINAL_DIMENSIONS // Use default if terminal size is unavailable.
         };
 
-        // Resize screen based on the terminal size and the dimensions of the pixel type.
-        self.resize(
-            terminal_width * T::WIDTH as u16, 
-            (terminal_height - 1) * T::HEIGHT as u16
+        // Reserve the last row of the terminal and any reserved columns, without underflowing on small terminals.
+        let (columns, rows) = (terminal_width.saturating_sub(self.reserved_columns), terminal_height.saturating_sub(1));
+
+        // Resize screen based on the terminal size and the dimensions of the pixel type, never going below the minimum.
+        self.fit_to_cells::<T>(
+            columns.max(MINIMUM_TERMINAL_DIMENSIONS.0),
+            rows.max(MINIMUM_TERMINAL_DIMENSIONS.1)
         );
+
+        columns >= MINIMUM_TERMINAL_DIMENSIONS.0 && rows >= MINIMUM_TERMINAL_DIMENSIONS.1
+    }
+
+    // Show a message in place of the model when the terminal is too small to render in.
+    pub fn render_too_small(&self) {
+        let columns = terminal::size().map(|(width, _)| width).unwrap_or(0) as usize;
+        let message: String = TOO_SMALL_MSG.chars().take(columns).collect();
+        execute!(
+            io::stdout(),
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0),
+            style::Print(message)
+        ).unwrap();
+    }
+
+    // Stamp a line of text in reverse video onto the last row of the terminal, which fit_to_terminal leaves free for it.
+    // The text is cut off or padded to the width of the terminal, so it always covers the whole row.
+    pub fn blit_status_line(&mut self, text: &str) {
+        let (columns, rows) = terminal::size().unwrap_or(DEFAULT_TERMINAL_DIMENSIONS);
+        let mut line: String = text.chars().take(columns as usize).collect();
+        line.extend(iter::repeat_n(' ', (columns as usize).saturating_sub(line.chars().count())));
+        self.blit_text((0, rows.saturating_sub(1)), &[&line], OverlayStyle { reverse: true, ..OverlayStyle::default() });
+    }
+
+    // Resize the screen to fit a given number of terminal cells of a specified pixel type.
+    // Sizes too large for the screen's dimensions are cut down to the largest it can hold rather than overflowing.
+    pub fn fit_to_cells<T: Pixel>(&mut self, columns: u16, rows: u16) {
+        self.resize(columns.saturating_mul(T::WIDTH as u16), rows.saturating_mul(T::HEIGHT as u16));
     }
 
     // Write a value to a specific coordinate on the screen, if within bounds.
     pub fn write(&mut self, val: bool, point: &Point) {
-        let x_in_bounds = 0 < point.x && point.x < self.width as i32;
-        let y_in_bounds = 0 < point.y && point.y < self.height as i32;
+        let x_in_bounds = 0 <= point.x && point.x < self.width as i32;
+        let y_in_bounds = 0 <= point.y && point.y < self.height as i32;
         if x_in_bounds && y_in_bounds {
+            if val { self.pixels_written += 1 }
             self.content[point.y as usize][point.x as usize] = val;
+            self.colors[point.y as usize][point.x as usize] = if val { self.pen } else { None };
+        }
+    }
+
+    // Draw a filled dot of a given radius centered on a point.
+    pub fn dot(&mut self, center: &Point, radius: i32) {
+        for delta_y in -radius..=radius {
+            for delta_x in -radius..=radius {
+                if delta_x * delta_x + delta_y * delta_y <= radius * radius {
+                    self.write(true, &Point::new(center.x + delta_x, center.y + delta_y));
+                }
+            }
         }
     }
 
+    // Stamp lines of text onto the overlay layer from an origin (column, row) in terminal cells, to be drawn over the pixels when rendering.
+    // Unlike the pixels, the text stays until the overlay is cleared. Text beyond the screen's cells, such as a status line below them,
+    // is still drawn on the terminal by render, but left out of the text conversions, which only cover the screen itself.
+    pub fn blit_text(&mut self, origin: (u16, u16), lines: &[&str], style: OverlayStyle) {
+        for (y, line) in lines.iter().enumerate() {
+            let row = origin.1 as usize + y;
+            if self.overlay.len() <= row {
+                self.overlay.resize(row + 1, Vec::new());
+            }
+            for (x, character) in line.chars().enumerate() {
+                let column = origin.0 as usize + x;
+                let cells = &mut self.overlay[row];
+                if cells.len() <= column {
+                    cells.resize(column + 1, None);
+                }
+                cells[column] = Some((character, style));
+            }
+        }
+    }
+
+    // Removes all text from the overlay layer, leaving the pixels underneath as they are.
+    pub fn clear_overlay(&mut self) {
+        self.overlay.clear();
+    }
+
+    // Get the overlay layer's text at a terminal cell (column, row), if there is any.
+    fn overlay_cell(&self, column: usize, row: usize) -> Option<(char, OverlayStyle)> {
+        self.overlay.get(row).and_then(|cells| cells.get(column)).copied().flatten()
+    }
+
     // Clears the entire screen by resetting the content to false (off).
     pub fn clear(&mut self) {
         self.content = vec![vec![false; self.width as usize]; self.height as usize];
+        self.colors = vec![vec![None; self.width as usize]; self.height as usize];
+        self.depth = vec![vec![f32::INFINITY; self.width as usize]; self.height as usize];
+        self.pixels_written = 0;
     }
 
-    // Resize the screen to a new width and height, adjusting content if necessary.
+    // Resize the screen to a new width and height, keeping the content in the overlapping region and clearing any new cells.
     pub fn resize(&mut self, width: u16, height: u16) {
-        // Handle resizing the height.
-        if height > self.height {
-            self.content.extend(vec![
-                vec![false; width as usize]; 
-                (height - self.height) as usize
-            ])
-        } else {
-            self.content.truncate(height as usize);
+        // Handle resizing the width of the existing rows first, so rows added below already have the new width.
+        for row in self.content.iter_mut() {
+            row.resize(width as usize, false);
+        }
+        for row in self.colors.iter_mut() {
+            row.resize(width as usize, None);
         }
+        for row in self.depth.iter_mut() {
+            row.resize(width as usize, f32::INFINITY);
+        }
+        self.width = width;
+
+        // Handle resizing the height.
+        self.content.resize(height as usize, vec![false; width as usize]);
+        self.colors.resize(height as usize, vec![None; width as usize]);
+        self.depth.resize(height as usize, vec![f32::INFINITY; width as usize]);
         self.height = height;
+    }
 
-        // Handle resizing the width.
-        if width > self.width {
-            for row in self.content.iter_mut() {
-                row.extend(vec![false; (width - self.width) as usize]);
+    // Record a depth over a disc of pixels around a center, keeping whichever depth is nearest at each pixel.
+    pub fn splat_depth(&mut self, center: &Point, radius: i32, depth: f32) {
+        for delta_y in -radius..=radius {
+            for delta_x in -radius..=radius {
+                let (x, y) = (center.x + delta_x, center.y + delta_y);
+                let in_bounds = 0 <= x && x < self.width as i32 && 0 <= y && y < self.height as i32;
+                if in_bounds && delta_x * delta_x + delta_y * delta_y <= radius * radius {
+                    let nearest = &mut self.depth[y as usize][x as usize];
+                    *nearest = nearest.min(depth);
+                }
             }
+        }
+    }
+
+    // Get whether a pixel is set and the color it was drawn with, ignoring inversion. The pixel must be on the screen.
+    pub fn pixel(&self, x: usize, y: usize) -> (bool, Option<style::Color>) {
+        (self.content[y][x], self.colors[y][x])
+    }
+
+    // Get the nearest depth recorded at a pixel, or None if the pixel is off the screen.
+    pub fn depth_at(&self, point: &Point) -> Option<f32> {
+        if 0 <= point.x && point.x < self.width as i32 && 0 <= point.y && point.y < self.height as i32 {
+            Some(self.depth[point.y as usize][point.x as usize])
         } else {
-            for row in self.content.iter_mut() {
-                row.truncate(width as usize);
+            None
+        }
+    }
+
+    // Clip a line to the screen rectangle, returning None if no part of it is visible.
+    fn clip_line(&self, start: &Point, end: &Point) -> Option<(Point, Point)> {
+        let (start, end) = ((start.x as f64, start.y as f64), (end.x as f64, end.y as f64));
+        let (t_enter, t_exit) = self.clip_fractions(start, end)?;
+        Some((Point::along(start, end, t_enter), Point::along(start, end, t_exit)))
+    }
+
+    // Clip a line between exact (unrounded) pixel positions to the screen rectangle using the Liang-Barsky algorithm.
+    // Returns the fractions of the way along the line where it enters and leaves the screen, or None if no part of it is visible.
+    // Clipping before rounding keeps the direction of lines whose ends are too far off-screen to fit in a pixel coordinate.
+    pub fn clip_fractions(&self, start: (f64, f64), end: (f64, f64)) -> Option<(f64, f64)> {
+        let (start_x, start_y) = start;
+        let (delta_x, delta_y) = (end.0 - start_x, end.1 - start_y);
+        let (max_x, max_y) = (self.width as f64 - 1., self.height as f64 - 1.);
+
+        // An empty screen has nothing to draw into, and a line with an unknown end can't be placed on it.
+        if max_x < 0. || max_y < 0. || ![start_x, start_y, delta_x, delta_y].iter().all(|value| value.is_finite()) {
+            return None;
+        }
+
+        // Each boundary is written as p * t <= q, where t runs from 0 at the start to 1 at the end of the line.
+        let boundaries = [
+            (-delta_x, start_x),  // Left edge.
+            (delta_x, max_x - start_x),  // Right edge.
+            (-delta_y, start_y),  // Top edge.
+            (delta_y, max_y - start_y),  // Bottom edge.
+        ];
+
+        let (mut t_enter, mut t_exit) = (0f64, 1f64);
+        for (p, q) in boundaries {
+            if p == 0. {
+                // The line is parallel to this boundary, so it is either fully inside or fully outside of it.
+                if q < 0. {
+                    return None;
+                }
+            } else if p < 0. {
+                t_enter = t_enter.max(q / p);
+            } else {
+                t_exit = t_exit.min(q / p);
             }
         }
-        self.width = width;
+
+        if t_enter > t_exit {
+            return None;
+        }
+
+        Some((t_enter, t_exit))
     }
 
     // Draw a line on the screen using Bresenham's line algorithm.
-    pub fn line(&mut self, start: &Point, end: &Point) {            
+    pub fn line(&mut self, start: &Point, end: &Point) {
+        self.walk_line(start, end, |screen, point| screen.write(true, point));
+    }
+
+    // Draw a line of a given width in pixels, by stamping a square brush along the line. A width of 1 draws a plain line.
+    pub fn line_thick(&mut self, start: &Point, end: &Point, width: u16) {
+        if width <= 1 {
+            return self.line(start, end);
+        }
+        self.walk_line(start, end, |screen, point| screen.stamp(point, width));
+    }
+
+    // Draw a line whose density fades from one intensity (0 to 1) at its start to another at its end, using ordered dithering.
+    pub fn line_faded(&mut self, start: &Point, end: &Point, start_intensity: f32, end_intensity: f32, width: u16) {
+        let origin = *start;
+        let length = ((end.x - start.x) as f32).hypot((end.y - start.y) as f32);
+        self.walk_line(start, end, |screen, point| {
+            // Interpolate the intensity by how far along the original (unclipped) line this pixel is.
+            let progress = if length > 0. {
+                ((point.x - origin.x) as f32).hypot((point.y - origin.y) as f32) / length
+            } else {
+                0.
+            };
+            let intensity = start_intensity + (end_intensity - start_intensity) * progress;
+            if intensity > dither_threshold(point) {
+                screen.stamp(point, width);
+            }
+        });
+    }
+
+    // Set a square brush of pixels of a given width, centered on a point as closely as possible.
+    fn stamp(&mut self, point: &Point, width: u16) {
+        let width = width.max(1) as i32;
+        for delta_y in -(width - 1) / 2..=width / 2 {
+            for delta_x in -(width - 1) / 2..=width / 2 {
+                self.write(true, &Point::new(point.x + delta_x, point.y + delta_y));
+            }
+        }
+    }
+
+    // Visit each pixel of a line using Bresenham's line algorithm, calling plot for each of them.
+    fn walk_line<F: FnMut(&mut Screen, &Point)>(&mut self, start: &Point, end: &Point, mut plot: F) {
+        // Clip the line to the screen first so the loop below only visits visible pixels.
+        let (start, end) = match self.clip_line(start, end) {
+            Some(clipped) => clipped,
+            None => return,
+        };
+
         let delta_x = (end.x - start.x).abs();
         let step_x: i32 = if start.x < end.x {1} else {-1};
         let delta_y = -(end.y - start.y).abs();
@@ -188,11 +692,9 @@ impl Screen {
         let mut x = start.x;
         let mut y = start.y;
 
-        self.write(true, &Point::new(x, y)); // Draw the starting point.
-
-        // Loop until the end point is reached.
+        // Loop until the end point is reached, drawing each point on the way to it.
         while !(x == end.x && y == end.y) {
-            self.write(true, &Point::new(x, y)); // Draw the current point.
+            plot(self, &Point::new(x, y)); // Draw the current point.
             let curr_err = err;
 
             if 2 * curr_err >= delta_y {
@@ -205,37 +707,476 @@ impl Screen {
                 y += step_y;
             }
         }
+
+        plot(self, &end); // Draw the end point.
     }
 
-    // Render the screen by outputting its content using the specified pixel type.
-    pub fn render<PixelType: Pixel>(&self) {
-        execute!(
-            io::stdout(),
-            cursor::MoveTo(0, 0) // Move cursor to the top-left corner.
-        ).unwrap();
+    // Fill a triangle using ordered dithering, so the density of set pixels is proportional to intensity (0 to 1).
+    // Pixels inside the triangle that fall below the threshold are cleared, letting nearer faces hide farther ones.
+    pub fn fill_dithered(&mut self, triangle: &[Point; 3], intensity: f32) {
+        self.walk_triangle(triangle, |screen, point, _| screen.write(intensity > dither_threshold(point), point));
+    }
+
+    // Fill a triangle with ordered dithering like fill_dithered, but only where it is nearer than anything drawn there before,
+    // given the depth of each corner. The depth is interpolated across the triangle and recorded, so triangles can come in any order.
+    pub fn fill_triangle(&mut self, triangle: &[Point; 3], depths: [f32; 3], intensity: f32) {
+        self.walk_triangle(triangle, |screen, point, weights| {
+            // Depth doesn't change linearly across the screen once it has been divided out by the perspective, but its reciprocal does.
+            let depth = 1. / (weights[0] / depths[0] + weights[1] / depths[1] + weights[2] / depths[2]);
+            let nearest = &mut screen.depth[point.y as usize][point.x as usize];
+            if depth < *nearest {
+                *nearest = depth;
+                screen.write(intensity > dither_threshold(point), point);
+            }
+        });
+    }
+
+    // Visit each on-screen pixel inside a triangle, calling plot for each of them with the pixel's barycentric weights,
+    // which say how much each corner contributes to it and add up to 1.
+    fn walk_triangle<F: FnMut(&mut Screen, &Point, [f32; 3])>(&mut self, triangle: &[Point; 3], mut plot: F) {
+        let [a, b, c] = triangle;
+
+        // Signed doubled area of the triangle formed by three points, positive for one winding and negative for the other.
+        let edge = |from: &Point, to: &Point, point: &Point| -> i64 {
+            (to.x as i64 - from.x as i64) * (point.y as i64 - from.y as i64)
+                - (to.y as i64 - from.y as i64) * (point.x as i64 - from.x as i64)
+        };
+
+        // Skip degenerate triangles that cover no area.
+        let area = edge(a, b, c);
+        if area == 0 {
+            return;
+        }
 
+        // Only visit the part of the triangle's bounding box that lies on the screen.
+        let min_x = a.x.min(b.x).min(c.x).max(0);
+        let max_x = a.x.max(b.x).max(c.x).min(self.width as i32 - 1);
+        let min_y = a.y.min(b.y).min(c.y).max(0);
+        let max_y = a.y.max(b.y).max(c.y).min(self.height as i32 - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let point = Point::new(x, y);
+                let weights = (edge(b, c, &point), edge(c, a, &point), edge(a, b, &point));
+
+                // The point is inside when all edge functions share the triangle's sign.
+                let inside = if area > 0 {
+                    weights.0 >= 0 && weights.1 >= 0 && weights.2 >= 0
+                } else {
+                    weights.0 <= 0 && weights.1 <= 0 && weights.2 <= 0
+                };
+
+                if inside {
+                    plot(self, &point, [weights.0 as f32 / area as f32, weights.1 as f32 / area as f32, weights.2 as f32 / area as f32]);
+                }
+            }
+        }
+    }
+
+    // Convert the screen's content into rows of characters using the specified pixel type, along with the color of each character.
+    // A character takes the color of the last colored pixel set within it, or None if all of its pixels use the default color.
+    pub fn to_cells<PixelType: Pixel>(&self) -> Vec<Vec<(char, Option<style::Color>)>> {
         // Split the content into chunks according to the height of the pixel type.
-        let chunked_rows = self.content.chunks(PixelType::HEIGHT);
+        let chunked_rows = self.content.chunks(PixelType::HEIGHT).zip(self.colors.chunks(PixelType::HEIGHT));
+        let real_row_width = (self.width as usize).div_ceil(PixelType::WIDTH);
 
-        // Iterate through each chunked row and render the appropriate characters.
-        for subrows in chunked_rows {
-            let real_row_width = self.width.div_ceil(PixelType::WIDTH as u16) as usize;
-            let mut real_row = vec![PixelType::new(); real_row_width];
+        // Iterate through each chunked row and build the appropriate characters.
+        chunked_rows.map(|(subrows, color_subrows)| {
+            let mut real_row = vec![(PixelType::new(), None); real_row_width];
 
-            // Convert booleans into pixels and update the content for rendering.
-            for y in 0..PixelType::HEIGHT {
-                for x in 0..real_row_width {
-                    let pix = &mut real_row[x];
+            // Convert booleans into pixels, treating anything past the edge of the content as off.
+            for (y, (subrow, color_subrow)) in subrows.iter().zip(color_subrows).enumerate() {
+                for (x, (pix, color)) in real_row.iter_mut().enumerate() {
                     for j in 0..PixelType::WIDTH {
-                        pix[y][j] = subrows[y as usize][x + j];
+                        let index = x * PixelType::WIDTH + j;
+                        let val = subrow.get(index).copied().unwrap_or(false);
+                        pix[y][j] = val != self.inverted;
+                        if let Some(Some(pixel_color)) = color_subrow.get(index) {
+                            *color = Some(*pixel_color);
+                        }
+                    }
+                }
+            }
+
+            real_row.iter().map(|(pixel, color)| (pixel.to_char(), *color)).collect()
+        }).collect()
+    }
+
+    // Convert the screen's content into rows of characters using the specified pixel type.
+    // The overlay layer's text takes the place of the characters underneath it.
+    pub fn to_rows<PixelType: Pixel>(&self) -> Vec<String> {
+        self.to_cells::<PixelType>()
+            .iter()
+            .enumerate()
+            .map(|(y, row)| row.iter().enumerate().map(|(x, &(character, _))| {
+                self.overlay_cell(x, y).map_or(character, |(overlay_character, _)| overlay_character)
+            }).collect())
+            .collect()
+    }
+
+    // Convert the screen's content into rows of characters with ANSI escape sequences, switching colors only where they change.
+    // The overlay layer's text takes the place of the characters underneath it.
+    // Each row ends with the default color restored, so rows can be printed independently.
+    fn to_colored_rows<PixelType: Pixel>(&self) -> Vec<String> {
+        self.to_cells::<PixelType>().iter().enumerate().map(|(y, row)| {
+            let mut text = String::new();
+            let mut current = None;
+            let mut current_overlay = None;  // Style of the overlay text being written, if any.
+            for (x, &(character, color)) in row.iter().enumerate() {
+                if let Some((overlay_character, overlay_style)) = self.overlay_cell(x, y) {
+                    if current_overlay != Some(overlay_style) {
+                        text.push_str(&overlay_style.escapes(self.color_mode));
+                        current_overlay = Some(overlay_style);
+                        current = None;
+                    }
+                    text.push(overlay_character);
+                    continue;
+                }
+                if current_overlay.take().is_some() {
+                    text.push_str(&style::SetAttribute(style::Attribute::Reset).to_string());
+                }
+
+                let color = color.and_then(|color| apply_color(color, self.color_mode));
+                // Blank characters show no color, so they keep whichever color is active.
+                if color != current && character != ' ' && character != '\u{2800}' {
+                    match color {
+                        Some(color) => text.push_str(&style::SetForegroundColor(color).to_string()),
+                        None => text.push_str(&style::ResetColor.to_string()),
+                    }
+                    current = color;
+                }
+                text.push(character);
+            }
+            if current_overlay.is_some() {
+                text.push_str(&style::SetAttribute(style::Attribute::Reset).to_string());
+            } else if current.is_some() {
+                text.push_str(&style::ResetColor.to_string());
+            }
+            text
+        }).collect()
+    }
+
+    // Convert the screen's content into plain text, with one line per row of characters.
+    pub fn to_plain_string<PixelType: Pixel>(&self) -> String {
+        self.to_rows::<PixelType>().join("\n")
+    }
+
+    // Convert the screen's content into text with ANSI escape sequences for its colors, ending with a style reset.
+    pub fn to_ansi_string<PixelType: Pixel>(&self) -> String {
+        format!("{}{}", self.to_colored_rows::<PixelType>().join("\n"), style::SetAttribute(style::Attribute::Reset))
+    }
+
+    // Convert the screen's content into an HTML <pre> block, putting each run of neighbouring cells of the same color in a colored <span>.
+    // Like the ANSI rows, blank characters join whichever run they are in, and no colors are written when colors are off.
+    pub fn to_html<PixelType: Pixel>(&self) -> String {
+        let rows: Vec<String> = self.to_cells::<PixelType>().iter().enumerate().map(|(y, row)| {
+            let mut text = String::new();
+            let mut current = None;
+            for (x, &(character, color)) in row.iter().enumerate() {
+                let (character, color) = match self.overlay_cell(x, y) {
+                    Some((overlay_character, overlay_style)) => (overlay_character, overlay_style.foreground),
+                    None => (character, color),
+                };
+                let color = color.filter(|_| self.color_mode != ColorMode::Monochrome).and_then(color_components);
+                if color != current && character != ' ' && character != '\u{2800}' {
+                    if current.is_some() {
+                        text.push_str("</span>");
+                    }
+                    if let Some([r, g, b]) = color {
+                        text.push_str(&format!("<span style=\"color:#{:02x}{:02x}{:02x}\">", r, g, b));
+                    }
+                    current = color;
+                }
+                match character {
+                    '&' => text.push_str("&amp;"),
+                    '<' => text.push_str("&lt;"),
+                    '>' => text.push_str("&gt;"),
+                    _ => text.push(character),
+                }
+            }
+            if current.is_some() {
+                text.push_str("</span>");
+            }
+            text
+        }).collect();
+        format!("<pre>\n{}\n</pre>", rows.join("\n"))
+    }
+
+    // Convert the screen's content into text in the specified format.
+    pub fn to_text<PixelType: Pixel>(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Plain => self.to_plain_string::<PixelType>(),
+            OutputFormat::Ansi => self.to_ansi_string::<PixelType>(),
+            OutputFormat::Html => self.to_html::<PixelType>(),
+        }
+    }
+
+    // Render the screen into a rectangle of the terminal whose top-left cell is at an origin (column, row), using the specified pixel type.
+    pub fn render_at<PixelType: Pixel>(&self, origin: (u16, u16)) {
+        render_views::<PixelType>(&[(self, origin)]);
+    }
+
+    // Append the screen's rows to a frame, moving the cursor to the start of each of them within the rectangle at an origin.
+    // Rows are positioned separately since a view narrower than the terminal can't rely on it wrapping between rows.
+    fn queue_at<PixelType: Pixel>(&self, frame: &mut Vec<u8>, origin: (u16, u16)) {
+        for (y, row) in self.to_colored_rows::<PixelType>().iter().enumerate() {
+            queue!(
+                frame,
+                cursor::MoveTo(origin.0, origin.1.saturating_add(y as u16))
+            ).unwrap();
+            frame.extend_from_slice(row.as_bytes());
+        }
+        self.queue_overlay_beyond::<PixelType>(frame, origin);
+    }
+
+    // Append the overlay layer's text lying beyond the screen's cells to a frame, such as a status line below them or a sidebar beside them.
+    // The text inside the cells is already part of the screen's rows, so each run of the rest is positioned relative to the origin instead.
+    fn queue_overlay_beyond<PixelType: Pixel>(&self, frame: &mut Vec<u8>, origin: (u16, u16)) {
+        let columns = (self.width as usize).div_ceil(PixelType::WIDTH);
+        let rows = (self.height as usize).div_ceil(PixelType::HEIGHT);
+        for (y, cells) in self.overlay.iter().enumerate() {
+            let mut x = if y < rows { columns } else { 0 };
+            while x < cells.len() {
+                if cells[x].is_none() {
+                    x += 1;
+                    continue;
+                }
+                queue!(
+                    frame,
+                    cursor::MoveTo(origin.0.saturating_add(x as u16), origin.1.saturating_add(y as u16))
+                ).unwrap();
+                let mut current = None;
+                while let Some(&Some((character, style))) = cells.get(x) {
+                    if current != Some(style) {
+                        frame.extend_from_slice(style.escapes(self.color_mode).as_bytes());
+                        current = Some(style);
                     }
+                    frame.extend_from_slice(character.to_string().as_bytes());
+                    x += 1;
                 }
+                frame.extend_from_slice(style::SetAttribute(style::Attribute::Reset).to_string().as_bytes());
             }
+        }
+    }
+
+    // Append the whole screen to a frame from the top-left corner of the terminal, ending each row with row_end.
+    fn queue_frame<PixelType: Pixel>(&self, frame: &mut Vec<u8>, row_end: &str) {
+        queue!(
+            frame,
+            cursor::MoveTo(0, 0) // Move cursor to the top-left corner.
+        ).unwrap();
+
+        // Append the character representation of each row, relying on the terminal to wrap between them unless they are ended.
+        for row in self.to_colored_rows::<PixelType>() {
+            frame.extend_from_slice(row.as_bytes());
+            frame.extend_from_slice(row_end.as_bytes());
+        }
+        self.queue_overlay_beyond::<PixelType>(frame, (0, 0));
+    }
+
+    // Render the screen by outputting its content using the specified pixel type.
+    // The whole frame is built in memory first and written at once, to avoid flushing stdout for every character.
+    pub fn render<PixelType: Pixel>(&self) {
+        // Rows narrower than the terminal don't wrap onto the next row, so they are positioned one by one instead.
+        if self.reserved_columns > 0 {
+            return self.render_at::<PixelType>((0, 0));
+        }
+
+        // Nothing wraps the rows when the output is redirected, such as into a file, so they are ended with newlines there instead.
+        let mut frame = Vec::<u8>::new();
+        self.queue_frame::<PixelType>(&mut frame, if io::stdout().is_terminal() { "" } else { "\n" });
+
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&frame).unwrap();
+        stdout.flush().unwrap();
+    }
+}
+
+// Render several screens into one terminal frame, each in the rectangle whose top-left cell is at its origin (column, row).
+// This composes split views, such as two models side by side, while still writing the whole frame at once.
+pub fn render_views<PixelType: Pixel>(views: &[(&Screen, (u16, u16))]) {
+    let mut frame = Vec::<u8>::new();
+    for (screen, origin) in views {
+        screen.queue_at::<PixelType>(&mut frame, *origin);
+    }
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&frame).unwrap();
+    stdout.flush().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Builds a screen of a given size in pixels with nothing drawn on it.
+    fn blank_screen(width: u16, height: u16) -> Screen {
+        let mut screen = Screen::new();
+        screen.resize(width, height);
+        screen
+    }
 
-            // Output the resulting character representation for the row.
-            for pixel in real_row {
-                print!("{}", pixel.to_char());
+    #[test]
+    fn lines_from_far_off_the_screen_only_visit_visible_pixels() {
+        let mut screen = blank_screen(20, 10);
+        screen.line(&Point::new(-1_000_000, -500_000), &Point::new(1_000_000, 500_000));
+
+        // The line runs through the top-left corner at half a pixel down per pixel across, leaving through the bottom row.
+        assert!(screen.content[5][10]);
+        assert!(screen.content[9].iter().any(|&pixel| pixel));
+        let drawn = screen.content.iter().flatten().filter(|&&pixel| pixel).count();
+        assert!(drawn < 30);
+    }
+
+    #[test]
+    fn lines_set_each_of_their_pixels_once() {
+        let mut screen = blank_screen(10, 3);
+        screen.line(&Point::new(1, 1), &Point::new(9, 1));
+        assert_eq!(screen.pixels_written, 9);
+        assert!((1..10).all(|x| screen.content[1][x]));
+    }
+
+    #[test]
+    fn lines_missing_the_screen_are_clipped_away() {
+        let screen = blank_screen(20, 10);
+        assert!(screen.clip_line(&Point::new(-50, -5), &Point::new(50, -1)).is_none());
+        let (start, end) = screen.clip_line(&Point::new(-10, -5), &Point::new(19, 9)).unwrap();
+        assert_eq!((start.x, start.y, end.x, end.y), (0, 0, 19, 9));
+    }
+
+    #[test]
+    fn html_wraps_the_plain_text_in_a_pre_block() {
+        let mut screen = blank_screen(4, 4);
+        screen.write(true, &Point::new(1, 1));
+        screen.write(true, &Point::new(2, 2));
+        assert_eq!(screen.to_html::<BlockPixel>(), format!("<pre>\n{}\n</pre>", screen.to_plain_string::<BlockPixel>()));
+        assert_eq!(screen.to_html::<BlockPixel>(), "<pre>\n▗ \n ▘\n</pre>");
+    }
+
+    #[test]
+    fn html_merges_runs_of_the_same_color() {
+        let mut screen = blank_screen(10, 2);
+        screen.pen = Some(style::Color::Red);
+        for x in 1..5 {
+            screen.write(true, &Point::new(x, 1));
+        }
+        screen.pen = Some(style::Color::Rgb { r: 0, g: 128, b: 255 });
+        screen.write(true, &Point::new(8, 1));
+
+        assert_eq!(
+            screen.to_html::<BlockPixel>(),
+            "<pre>\n<span style=\"color:#ff0000\">▗▄▖ </span><span style=\"color:#0080ff\">▖</span>\n</pre>",
+        );
+    }
+
+    #[test]
+    fn html_has_no_colors_when_colors_are_off() {
+        let mut screen = blank_screen(2, 2);
+        screen.color_mode = ColorMode::Monochrome;
+        screen.pen = Some(style::Color::Red);
+        screen.write(true, &Point::new(1, 1));
+        assert_eq!(screen.to_html::<BlockPixel>(), "<pre>\n▗\n</pre>");
+    }
+
+    #[test]
+    fn degenerate_screens_draw_nothing() {
+        for (width, height) in [(0, 0), (0, 7), (7, 0), (1, 1)] {
+            let mut screen = blank_screen(width, height);
+            screen.line(&Point::new(-5, -5), &Point::new(5, 5));
+            screen.fill_dithered(&[Point::new(-3, -3), Point::new(3, -3), Point::new(0, 3)], 1.);
+            let drawn = screen.to_plain_string::<BrailePixel>();
+            if width == 0 || height == 0 {
+                assert!(drawn.chars().all(|character| character == '\n'));
             }
         }
     }
+
+    #[test]
+    fn resizing_keeps_the_overlapping_pixels() {
+        let mut screen = blank_screen(4, 3);
+        screen.write(true, &Point::new(1, 1));
+        screen.write(true, &Point::new(3, 2));
+
+        screen.resize(6, 5);
+        assert_eq!(screen.content, vec![
+            vec![false; 6],
+            vec![false, true, false, false, false, false],
+            vec![false, false, false, true, false, false],
+            vec![false; 6],
+            vec![false; 6],
+        ]);
+
+        screen.resize(2, 2);
+        assert_eq!(screen.content, vec![vec![false, false], vec![false, true]]);
+
+        // Growing again after shrinking fills the new pixels with nothing rather than what was cut off.
+        screen.resize(4, 3);
+        assert_eq!(screen.content, vec![vec![false; 4], vec![false, true, false, false], vec![false; 4]]);
+        screen.resize(2, 4);
+        assert_eq!(screen.content, vec![vec![false; 2], vec![false, true], vec![false; 2], vec![false; 2]]);
+    }
+
+    #[test]
+    fn runs_of_one_color_switch_to_it_once() {
+        let red = style::Color::Rgb { r: 255, g: 0, b: 0 };
+        let mut screen = blank_screen(12, 2);
+        screen.color_mode = ColorMode::TrueColor;
+        screen.pen = Some(red);
+        screen.line(&Point::new(1, 1), &Point::new(3, 1));
+        // Blank cells between the lines show no color, so the run carries on over them.
+        screen.line(&Point::new(8, 1), &Point::new(11, 1));
+
+        let row = &screen.to_colored_rows::<BlockPixel>()[0];
+        assert_eq!(row.matches(&style::SetForegroundColor(red).to_string()).count(), 1);
+        assert_eq!(row.matches("\x1b[38;").count(), 1);
+        assert!(row.ends_with(&style::ResetColor.to_string()));
+    }
+
+    #[test]
+    fn redirected_frames_have_a_line_per_row() {
+        let mut screen = blank_screen(6, 8);
+        screen.line(&Point::new(1, 1), &Point::new(5, 7));
+        let rows = screen.to_rows::<BlockPixel>().len();
+
+        let mut frame = Vec::new();
+        screen.queue_frame::<BlockPixel>(&mut frame, "\n");
+        let frame = String::from_utf8(frame).unwrap();
+        assert_eq!(frame.lines().count(), rows);
+        assert!(frame.ends_with('\n'));
+
+        let mut frame = Vec::new();
+        screen.queue_frame::<BlockPixel>(&mut frame, "");
+        assert!(!frame.contains(&b'\n'));
+    }
+
+    #[test]
+    fn overlay_text_covers_the_pixels_until_it_is_cleared() {
+        let mut screen = blank_screen(8, 4);
+        screen.line(&Point::new(1, 1), &Point::new(7, 1));
+        // Text running past the screen's cells is left out of its rows.
+        screen.blit_text((1, 0), &["ab", "beyond"], OverlayStyle::default());
+        assert_eq!(screen.to_rows::<BlockPixel>(), vec!["▗ab▄", " bey"]);
+
+        // Clearing the pixels leaves the overlay, and clearing the overlay leaves the pixels.
+        screen.clear();
+        assert_eq!(screen.to_rows::<BlockPixel>()[0], " ab ");
+        screen.line(&Point::new(1, 1), &Point::new(7, 1));
+        screen.clear_overlay();
+        assert_eq!(screen.to_rows::<BlockPixel>()[0], "▗▄▄▄");
+    }
+
+    #[test]
+    fn writes_reach_every_edge_of_the_screen() {
+        let mut screen = blank_screen(5, 3);
+        screen.write(true, &Point::new(0, 0));
+        screen.write(true, &Point::new(4, 2));
+        assert!(screen.content[0][0] && screen.content[2][4]);
+        assert_eq!(screen.pixels_written, 2);
+
+        // Anything just past the edges is still left out.
+        for point in [Point::new(-1, 0), Point::new(0, -1), Point::new(5, 2), Point::new(4, 3)] {
+            screen.write(true, &point);
+        }
+        assert_eq!(screen.pixels_written, 2);
+    }
 }
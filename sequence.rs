@@ -0,0 +1,82 @@
+use crate::model;
+use std::*;
+use time::{Duration, Instant};
+
+// A sequence of model files played back in order like an animation, such as the timesteps of a simulation.
+pub struct Sequence {
+    // Paths of the frames, in playback order.
+    pub paths: Vec<String>,
+    // Index into `paths` of the frame being shown.
+    pub index: usize,
+    // Whether playback is paused, leaving only manual stepping.
+    pub paused: bool,
+    // Time each frame is shown for.
+    interval: Duration,
+    // When the frame being shown was reached.
+    last_step: Instant,
+    // Whether the frame changed since it was last reported by poll.
+    changed: bool,
+}
+
+impl Sequence {
+    // Collects the frames of a sequence from paths that are either model files or directories of them, playing them at a given rate.
+    // Each directory contributes its model files sorted by name, so numbered timesteps play in order.
+    pub fn new(paths: &[String], frames_per_second: f32) -> Result<Sequence, Box<dyn error::Error>> {
+        let mut frames = Vec::<String>::new();
+        for path in paths {
+            if !path::Path::new(path).is_dir() {
+                frames.push(path.clone());
+                continue;
+            }
+
+            // Only files in one of the known model formats are picked up from directories.
+            let mut files: Vec<String> = fs::read_dir(path)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .map(|file| file.to_string_lossy().into_owned())
+                .filter(|file| model::format_of(file).is_some())
+                .collect();
+            files.sort();
+            frames.extend(files);
+        }
+
+        if frames.is_empty() {
+            return Err(Box::from("No model files found for the sequence."));
+        }
+
+        Ok(Sequence {
+            paths: frames,
+            index: 0,
+            paused: false,
+            interval: Duration::from_secs_f32(1. / frames_per_second),
+            last_step: Instant::now(),
+            changed: false,
+        })
+    }
+
+    // Moves a number of frames forwards (or backwards if negative), wrapping around at either end.
+    pub fn step(&mut self, frames: isize) {
+        let count = self.paths.len() as isize;
+        self.index = (self.index as isize + frames).rem_euclid(count) as usize;
+        self.last_step = Instant::now();
+        self.changed = true;
+    }
+
+    // Returns whether the frames are changing by themselves, which needs them to be unpaused and more than one.
+    pub fn playing(&self) -> bool {
+        !self.paused && self.paths.len() > 1
+    }
+
+    // Advances to the next frame once the current one has been shown long enough, unless paused.
+    // Returns the path of the frame to show whenever it changed, whether by playback or stepping.
+    pub fn poll(&mut self) -> Option<&str> {
+        if self.playing() && self.last_step.elapsed() >= self.interval {
+            self.step(1);
+        }
+
+        if !self.changed {
+            return None;
+        }
+        self.changed = false;
+        Some(&self.paths[self.index])
+    }
+}
@@ -0,0 +1,139 @@
+use crate::screen;
+use std::*;
+
+// Named views that can be put on a contact sheet, as the yaw and pitch of the camera looking at the model.
+// "front" looks along the Z axis, as the camera does by default, and "iso" looks down on the model from a corner.
+pub const VIEWS: [(&str, f32, f32); 7] = [
+    ("front", 0., 0.),
+    ("back", f32::consts::PI, 0.),
+    ("left", f32::consts::FRAC_PI_2, 0.),
+    ("right", -f32::consts::FRAC_PI_2, 0.),
+    ("top", 0., -f32::consts::FRAC_PI_2),
+    ("bottom", 0., f32::consts::FRAC_PI_2),
+    ("iso", f32::consts::FRAC_PI_4, -0.6154797),  // The pitch is -atan(1 / sqrt(2)), looking along a diagonal of a cube.
+];
+
+const BACKGROUND_COLOR: [u8; 3] = [0, 0, 0];  // Color of blank pixels, or of set ones on inverted screens.
+const FOREGROUND_COLOR: [u8; 3] = [255, 255, 255];  // Color of set pixels without a color of their own.
+const SEPARATOR_COLOR: [u8; 3] = [64, 64, 64];  // Color of the lines between tiles.
+const SEPARATOR_WIDTH: usize = 2;  // Width of the lines between tiles, in image pixels.
+const LABEL_COLOR: [u8; 3] = [255, 200, 0];  // Color of the view names, standing out from the model in either polarity.
+const LABEL_SCALE: usize = 2;  // Size of each pixel of the label font, in image pixels.
+const LABEL_MARGIN: usize = 2;  // Distance from the corner of a tile to its label, in image pixels.
+
+// A 3x5 pixel font for labels, with each row of a glyph given as 3 bits from left to right. Letters are drawn as capitals.
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const FONT: [[u8; GLYPH_HEIGHT]; 26] = [
+    [0b010, 0b101, 0b111, 0b101, 0b101],  // A
+    [0b110, 0b101, 0b110, 0b101, 0b110],  // B
+    [0b011, 0b100, 0b100, 0b100, 0b011],  // C
+    [0b110, 0b101, 0b101, 0b101, 0b110],  // D
+    [0b111, 0b100, 0b110, 0b100, 0b111],  // E
+    [0b111, 0b100, 0b110, 0b100, 0b100],  // F
+    [0b011, 0b100, 0b101, 0b101, 0b011],  // G
+    [0b101, 0b101, 0b111, 0b101, 0b101],  // H
+    [0b111, 0b010, 0b010, 0b010, 0b111],  // I
+    [0b001, 0b001, 0b001, 0b101, 0b010],  // J
+    [0b101, 0b101, 0b110, 0b101, 0b101],  // K
+    [0b100, 0b100, 0b100, 0b100, 0b111],  // L
+    [0b101, 0b111, 0b111, 0b101, 0b101],  // M
+    [0b110, 0b101, 0b101, 0b101, 0b101],  // N
+    [0b010, 0b101, 0b101, 0b101, 0b010],  // O
+    [0b110, 0b101, 0b110, 0b100, 0b100],  // P
+    [0b010, 0b101, 0b101, 0b110, 0b011],  // Q
+    [0b110, 0b101, 0b110, 0b101, 0b101],  // R
+    [0b011, 0b100, 0b010, 0b001, 0b110],  // S
+    [0b111, 0b010, 0b010, 0b010, 0b010],  // T
+    [0b101, 0b101, 0b101, 0b101, 0b111],  // U
+    [0b101, 0b101, 0b101, 0b101, 0b010],  // V
+    [0b101, 0b101, 0b111, 0b111, 0b101],  // W
+    [0b101, 0b101, 0b010, 0b101, 0b101],  // X
+    [0b101, 0b101, 0b010, 0b010, 0b010],  // Y
+    [0b111, 0b001, 0b010, 0b100, 0b111],  // Z
+];
+
+// Returns the yaw and pitch of a named view, or None if there is no view with that name.
+pub fn view_angles(name: &str) -> Option<(f32, f32)> {
+    VIEWS.iter().find(|(view, _, _)| *view == name).map(|&(_, yaw, pitch)| (yaw, pitch))
+}
+
+// One rendered view on a contact sheet, as the colors of its pixels and the name it is labeled with.
+pub struct Tile {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,  // Colors of the pixels row by row, from the top left.
+    label: String,
+}
+
+impl Tile {
+    // Takes a tile from the pixels of a rendered screen, one image pixel per screen pixel.
+    pub fn new(screen: &screen::Screen, label: &str) -> Tile {
+        let (width, height) = (screen.width as usize, screen.height as usize);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let (set, color) = screen.pixel(x, y);
+                pixels.push(match (set != screen.inverted, color) {
+                    (false, _) => BACKGROUND_COLOR,
+                    (true, Some(color)) => screen::color_components(color).unwrap_or(FOREGROUND_COLOR),
+                    (true, None) => FOREGROUND_COLOR,
+                });
+            }
+        }
+        Tile { width, height, pixels, label: String::from(label) }
+    }
+
+    // Draws the tile's label over its top left corner.
+    fn draw_label(&mut self) {
+        let mut left = LABEL_MARGIN;
+        for character in self.label.to_ascii_uppercase().chars() {
+            if character.is_ascii_uppercase() {
+                for (row, bits) in FONT[(character as u8 - b'A') as usize].iter().enumerate() {
+                    for column in 0..GLYPH_WIDTH {
+                        if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                            continue;
+                        }
+                        let (x, y) = (left + column * LABEL_SCALE, LABEL_MARGIN + row * LABEL_SCALE);
+                        for delta_y in 0..LABEL_SCALE {
+                            for delta_x in 0..LABEL_SCALE {
+                                if x + delta_x < self.width && y + delta_y < self.height {
+                                    self.pixels[(y + delta_y) * self.width + x + delta_x] = LABEL_COLOR;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            left += (GLYPH_WIDTH + 1) * LABEL_SCALE;  // Characters without a glyph leave a space.
+        }
+    }
+}
+
+// Labels tiles with their names and writes them out as a binary PPM image, in a grid that is about as many tiles wide as tall.
+// Tiles are placed row by row in the order given, with lines between them; all tiles should be the same size.
+pub fn write_contact_sheet<W: io::Write>(mut tiles: Vec<Tile>, out: &mut W) -> io::Result<()> {
+    let count = tiles.len().max(1);
+    let columns = (1..=count).find(|columns| columns * columns >= count).unwrap_or(count);
+    let rows = count.div_ceil(columns);
+    let tile_width = tiles.iter().map(|tile| tile.width).max().unwrap_or(0);
+    let tile_height = tiles.iter().map(|tile| tile.height).max().unwrap_or(0);
+    let width = columns * tile_width + (columns - 1) * SEPARATOR_WIDTH;
+    let height = rows * tile_height + (rows - 1) * SEPARATOR_WIDTH;
+
+    // Start with the separators everywhere, then copy each tile into its cell of the grid.
+    let mut image = vec![SEPARATOR_COLOR; width * height];
+    for (index, tile) in tiles.iter_mut().enumerate() {
+        tile.draw_label();
+        let left = (index % columns) * (tile_width + SEPARATOR_WIDTH);
+        let top = (index / columns) * (tile_height + SEPARATOR_WIDTH);
+        for y in 0..tile.height {
+            let start = (top + y) * width + left;
+            image[start..start + tile.width].copy_from_slice(&tile.pixels[y * tile.width..(y + 1) * tile.width]);
+        }
+    }
+
+    write!(out, "P6\n{} {}\n255\n", width, height)?;
+    out.write_all(&image.concat())?;
+    out.flush()
+}
@@ -1,4 +1,46 @@
 use crate::{model, screen};
+use std::*;
+use crossterm::style::Color;
+
+// Default limit on the camera's pitch, just under straight up or down so the view never flips over.
+const DEFAULT_PITCH_LIMIT: f32 = f32::consts::FRAC_PI_2 - 0.01;
+
+// Radius, in screen pixels, of the dots used to highlight vertices.
+const VERTEX_MARKER_RADIUS: i32 = 1;
+
+// Farthest, in screen pixels, that a vertex can be drawn from where the screen is clicked and still be picked.
+const PICK_RADIUS: f64 = 4.;
+
+// Radius, in screen pixels, of the disc each point covers when hiding the points behind it.
+const POINT_SPLAT_RADIUS: i32 = 2;
+// Steepest slope, as depth gained per unit across the screen, of a surface whose neighboring points don't hide each other.
+// A point is still drawn when it is at most this much farther than the nearest splat over the width of a splat.
+const POINT_SURFACE_SLOPE: f32 = 4.;
+
+// Colors of edges shared by only one face (holes in the mesh) and by more than two faces (non-manifold edges).
+const BOUNDARY_EDGE_COLOR: Color = Color::Red;
+const NON_MANIFOLD_EDGE_COLOR: Color = Color::Yellow;
+
+// Color of the model's bounding box.
+const BOUNDING_BOX_COLOR: Color = Color::Cyan;
+
+// Color and radius, in screen pixels, of the markers showing where the lights are, and how far out from the model's center
+// they are drawn, relative to the radius of its bounding sphere.
+const LIGHT_MARKER_COLOR: Color = Color::Yellow;
+const LIGHT_MARKER_RADIUS: i32 = 2;
+const LIGHT_MARKER_DISTANCE: f32 = 1.2;
+
+// Lights used for shading when none are given, as camera-space directions towards them and intensities, so that they follow the camera:
+// a bright key light from the upper left and a dimmer fill light from the lower right, lighting faces the key light only grazes.
+const DEFAULT_LIGHTS: [(f32, f32, f32, f32); 2] = [(-0.4, 0.5, -1., 0.8), (0.6, -0.2, -1., 0.35)];
+
+// How edges are picked when a model has more of them than the edge budget.
+#[derive(Copy, Clone, PartialEq)]
+pub enum EdgePriority {
+    Spread,  // Evenly spaced edges, however they look on screen.
+    Length,  // The edges that are longest on screen.
+    Silhouette,  // The edges on the model's outline first, then the longest on screen.
+}
 
 // A struct that represents a 3D point in space with x, y, and z coordinates.
 #[derive(Copy, Clone)]
@@ -13,6 +55,210 @@ impl Point {
     pub fn new(x: f32, y: f32, z: f32) -> Self {
         Point { x, y, z }
     }
+
+    // Returns the dot product of two vectors.
+    pub fn dot(&self, other: &Point) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    // Returns the cross product of two vectors, which is perpendicular to both.
+    pub fn cross(&self, other: &Point) -> Point {
+        Point::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    // Returns the length of a vector.
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    // Returns the vector scaled to unit length, leaving zero-length vectors as they are.
+    pub fn normalized(&self) -> Point {
+        let length = self.length();
+        if length == 0. {
+            return *self;
+        }
+        *self * (1. / length)
+    }
+
+    // Returns the smallest and largest of each coordinate of two points, for growing bounding boxes.
+    pub fn min(&self, other: &Point) -> Point {
+        Point::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+    pub fn max(&self, other: &Point) -> Point {
+        Point::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+}
+
+// Component-wise addition and subtraction of points, treating them as vectors.
+impl ops::Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl ops::Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+// Scaling of a point by a factor, treating it as a vector.
+impl ops::Mul<f32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: f32) -> Point {
+        Point::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+// A plane that hides everything on the side its normal points towards, for section views.
+#[derive(Copy, Clone)]
+pub struct ClipPlane {
+    pub point: Point,  // Any point on the plane.
+    pub normal: Point,  // Direction pointing into the hidden side.
+}
+
+impl ClipPlane {
+    // Returns the signed distance (scaled by the normal's length) of a point from the plane, positive on the hidden side.
+    pub fn distance(&self, point: &Point) -> f32 {
+        (*point - self.point).dot(&self.normal)
+    }
+}
+
+// A directional light used for shading.
+#[derive(Copy, Clone)]
+pub struct Light {
+    pub direction: Point,  // Unit direction towards the light.
+    pub intensity: f32,  // Brightness the light gives a face turned straight towards it, from 0 to 1.
+}
+
+// Returns the default lights, in camera space.
+fn default_lights() -> Vec<Light> {
+    DEFAULT_LIGHTS
+        .iter()
+        .map(|&(x, y, z, intensity)| Light { direction: Point::new(x, y, z).normalized(), intensity })
+        .collect()
+}
+
+// Computes the flat-shading intensity (0 to 1) of a camera-space triangle lit from a unit camera-space direction.
+fn flat_intensity(triangle: &[Point; 3], light: &Point) -> f32 {
+    let [a, b, c] = triangle;
+
+    // The face normal is the cross product of two of the triangle's edges.
+    let normal = (*b - *a).cross(&(*c - *a));
+    let length = normal.length();
+    if length == 0. {
+        return 0.;
+    }
+
+    // Faces pointing straight at the light are brightest, regardless of winding.
+    (normal.dot(light) / length).abs()
+}
+
+// Which parts of the model are drawn.
+#[derive(Copy, Clone, PartialEq)]
+pub enum DisplayMode {
+    Edges,  // Only the edges.
+    Points,  // Only the vertices.
+    Both,  // The edges, with highlighted vertices on top.
+}
+
+impl DisplayMode {
+    // Returns the name of the mode, as shown in the status line.
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayMode::Edges => "edges",
+            DisplayMode::Points => "points",
+            DisplayMode::Both => "edges and points",
+        }
+    }
+
+    // Returns the mode that follows this one when cycling through them.
+    pub fn next(self) -> DisplayMode {
+        match self {
+            DisplayMode::Edges => DisplayMode::Points,
+            DisplayMode::Points => DisplayMode::Both,
+            DisplayMode::Both => DisplayMode::Edges,
+        }
+    }
+}
+
+// Modes controlling how render_frame draws a model.
+pub struct RenderOptions {
+    // Which parts of the model are drawn.
+    pub display_mode: DisplayMode,
+    // Which kind of pixels to draw with.
+    pub pixel_mode: screen::PixelMode,
+    // Whether to fill the faces with dithered flat shading underneath the edges.
+    pub shading: bool,
+    // Whether to draw the faces filled with dithered flat shading in place of the edges and points, hiding what is behind them.
+    pub filled_faces: bool,
+    // Whether to highlight boundary and non-manifold edges.
+    pub manifold_edges: bool,
+    // Whether to draw the model's bounding box.
+    pub bounding_box: bool,
+    // Whether to mark where the lights are around the model.
+    pub light_markers: bool,
+    // Whether points hide the points behind them when only the points are drawn.
+    pub occlude_points: bool,
+    // Size of the frame in terminal cells and the format to return it in, or None to fit the frame to the terminal and draw it there.
+    pub headless: Option<(u16, u16, screen::OutputFormat)>,
+}
+
+impl RenderOptions {
+    // Creates options for drawing the edges of a model on the terminal with Braille pixels.
+    pub fn new() -> RenderOptions {
+        RenderOptions {
+            display_mode: DisplayMode::Edges,
+            pixel_mode: screen::PixelMode::Braille,
+            shading: false,
+            filled_faces: false,
+            manifold_edges: false,
+            bounding_box: false,
+            light_markers: false,
+            occlude_points: false,
+            headless: None,
+        }
+    }
+}
+
+// Converts a material's diffuse color (components from 0 to 1) into a terminal color.
+pub fn terminal_color(color: Option<[f32; 3]>) -> Option<Color> {
+    let [r, g, b] = color?.map(|component| (component.clamp(0., 1.) * 255.).round() as u8);
+    Some(Color::Rgb { r, g, b })
+}
+
+// Picks a deterministic pseudo-random color for a face from its index and a seed, using the SplitMix64 mixing function.
+// Components are kept away from black so every face stays visible.
+fn debug_face_color(seed: u64, index: usize) -> Color {
+    let mut hash = seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    hash ^= hash >> 31;
+
+    let component = |shift: u32| 64 + ((hash >> shift) & 0xFF) as u8 % 192;
+    Color::Rgb { r: component(0), g: component(8), b: component(16) }
+}
+
+// Counts of what a frame considered drawing and what it actually drew once clipping, culling and the edge budget had their say.
+#[derive(Copy, Clone, Default)]
+pub struct RenderStats {
+    // Edges of the model and its overlays, and how many of them reached the screen.
+    pub edges_considered: usize,
+    pub edges_drawn: usize,
+    // Triangles of the model when shading, and how many of them were filled.
+    pub triangles_considered: usize,
+    pub triangles_drawn: usize,
+    // Pixels set, counting pixels set more than once each time.
+    pub pixels_written: usize,
 }
 
 // The Camera struct represents the camera's position and orientation in 3D space.
@@ -31,6 +277,37 @@ pub struct Camera {
     // The camera's field of view (FOV) in radians.
     pub viewport_fov: f32,
 
+    // The largest pitch (in either direction) allowed by set_angles, or None to allow any pitch.
+    pub pitch_limit: Option<f32>,
+
+    // Width of the lines used to draw edges, in screen pixels.
+    pub line_width: u16,
+
+    // Depths at which fog starts fading edges and fully hides them, or None to disable fog.
+    pub fog: Option<(f32, f32)>,
+
+    // Plane hiding the geometry on one side of it, or None to show everything.
+    pub clip_plane: Option<ClipPlane>,
+
+    // Largest number of edges to draw, or None to draw them all, and how the edges to draw are picked from bigger models.
+    pub edge_budget: Option<usize>,
+    pub edge_priority: EdgePriority,
+
+    // Seed for giving every face its own pseudo-random color instead of its material's, or None to use the materials.
+    pub face_color_seed: Option<u64>,
+
+    // Lights in world space, whose contributions are added up when shading, or empty to use the default lights following the camera.
+    pub lights: Vec<Light>,
+
+    // Brightness (0 to 1) added to every shaded face, so that faces turned away from the lights aren't black.
+    pub ambient: f32,
+
+    // Whether to leave out the triangles of closed meshes that are turned away from the camera, along with the edges only they share.
+    pub cull_backfaces: bool,
+
+    // What the last frame drew, counted by the plot methods as they go and reset by render_frame.
+    pub stats: RenderStats,
+
     // A reference to the screen where the 3D model will be rendered.
     pub screen: screen::Screen,
 }
@@ -52,10 +329,119 @@ impl Camera {
             roll,
             viewport_distance,
             viewport_fov,
+            pitch_limit: Some(DEFAULT_PITCH_LIMIT),
+            line_width: 1,
+            fog: None,
+            clip_plane: None,
+            edge_budget: None,
+            edge_priority: EdgePriority::Silhouette,
+            face_color_seed: None,
+            lights: Vec::new(),
+            ambient: 0.,
+            cull_backfaces: false,
+            stats: RenderStats::default(),
             screen: screen::Screen::new(),
         }
     }
 
+    // Creates a Camera at an eye point looking towards a target point, turned about its view direction so that a given up direction
+    // points up on screen as closely as it can. The eye and target must differ, and up must not be parallel to the view direction.
+    pub fn from_eye_target_up(eye: Point, target: Point, up: Point, viewport_distance: f32, viewport_fov: f32) -> Self {
+        let forward = (target - eye).normalized();
+        let mut camera = Camera::new(eye, forward.x.atan2(forward.z), forward.y.clamp(-1., 1.).asin(), 0., viewport_distance, viewport_fov);
+
+        // Rolling turns the camera's up direction from where it points without roll towards its right direction,
+        // so the roll is the angle of the wanted up direction, flattened onto the viewport, between the two.
+        let (unrolled_up, unrolled_right) = (camera.direction_to_world(&Point::new(0., 1., 0.)), camera.direction_to_world(&Point::new(1., 0., 0.)));
+        let flat_up = up - forward * up.dot(&forward);
+        camera.roll = flat_up.dot(&unrolled_right).atan2(flat_up.dot(&unrolled_up));
+        camera
+    }
+
+    // Sets the camera's orientation, wrapping yaw into (-PI, PI] and clamping pitch to the pitch limit if there is one.
+    pub fn set_angles(&mut self, yaw: f32, pitch: f32, roll: f32) {
+        self.yaw = (yaw + f32::consts::PI).rem_euclid(f32::consts::TAU) - f32::consts::PI;
+        self.pitch = match self.pitch_limit {
+            Some(limit) => pitch.clamp(-limit, limit),
+            None => pitch,
+        };
+        self.roll = roll;
+    }
+
+    // Returns the direction the camera faces in world space, from undoing its pitch and yaw rotations.
+    fn forward(&self) -> Point {
+        Point::new(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        )
+    }
+
+    // Moves the camera onto a sphere around a target, so that it looks at the target from a given distance along its current yaw and pitch.
+    pub fn orbit(&mut self, target: &Point, distance: f32) {
+        self.coordinates = *target - self.forward() * distance;
+    }
+
+    // Fixes the default lights in place where they are, so that they stop following the camera.
+    // Does nothing if lights were already given.
+    pub fn fix_lights(&mut self) {
+        if self.lights.is_empty() {
+            self.lights = default_lights()
+                .iter()
+                .map(|light| Light { direction: self.direction_to_world(&light.direction), ..*light })
+                .collect();
+        }
+    }
+
+    // Rotates the lights around the world's vertical axis by an angle in radians, fixing the default lights in place first.
+    pub fn rotate_light(&mut self, angle: f32) {
+        self.orbit_light(angle, 0.);
+    }
+
+    // Orbits the lights around the model like the camera orbits it: by a yaw angle around the world's vertical axis, then
+    // by a pitch angle around the camera's horizontal axis, both in radians. The default lights are fixed in place first.
+    pub fn orbit_light(&mut self, yaw: f32, pitch: f32) {
+        self.fix_lights();
+        let right = self.direction_to_world(&Point::new(1., 0., 0.));
+        for light in self.lights.iter_mut() {
+            let direction = rotate_around(&light.direction, &Point::new(0., 1., 0.), yaw);
+            light.direction = rotate_around(&direction, &right, pitch).normalized();
+        }
+    }
+
+    // Converts a direction from world space into camera space, by rotating it without moving it from the camera's origin.
+    fn direction_to_camera(&self, direction: &Point) -> Point {
+        self.world_to_camera(&(self.coordinates + *direction))
+    }
+
+    // Returns how far to move the camera and the point it orbits to pan the view by distances along the camera's right and up directions.
+    pub fn pan_offset(&self, right: f32, up: f32) -> Point {
+        self.direction_to_world(&Point::new(right, up, 0.))
+    }
+
+    // Converts a direction from camera space into world space, undoing the rotations of world_to_camera in reverse order: roll, then pitch, then yaw.
+    fn direction_to_world(&self, direction: &Point) -> Point {
+        let (sin_yaw, sin_pitch, sin_roll) = (self.yaw.sin(), self.pitch.sin(), self.roll.sin());
+        let (cos_yaw, cos_pitch, cos_roll) = (self.yaw.cos(), self.pitch.cos(), self.roll.cos());
+        let (camera_x, camera_y, camera_z) = (direction.x, direction.y, direction.z);
+
+        let (pitch_x, pitch_y, pitch_z) = (
+            camera_x * cos_roll + camera_y * sin_roll,
+            -camera_x * sin_roll + camera_y * cos_roll,
+            camera_z,
+        );
+        let (rot_x, rot_y, rot_z) = (
+            pitch_x,
+            pitch_y * cos_pitch + pitch_z * sin_pitch,
+            -pitch_y * sin_pitch + pitch_z * cos_pitch,
+        );
+        Point::new(
+            rot_x * cos_yaw + rot_z * sin_yaw,
+            rot_y,
+            -rot_x * sin_yaw + rot_z * cos_yaw,
+        )
+    }
+
     // Converts a world coordinate (point) into camera space by applying the inverse of the camera's transformations (yaw, pitch, roll).
     fn world_to_camera(&self, world_point: &Point) -> Point {
         // Precompute sine and cosine of yaw, pitch, and roll for use in rotations.
@@ -63,50 +449,105 @@ impl Camera {
         let (cos_yaw, cos_pitch, cos_roll) = (self.yaw.cos(), self.pitch.cos(), self.roll.cos());
 
         // Calculate the relative position of the world point from the camera's coordinates.
-        let delta_x = world_point.x - self.coordinates.x;
-        let delta_y = world_point.y - self.coordinates.y;
-        let delta_z = world_point.z - self.coordinates.z;
+        let delta = *world_point - self.coordinates;
 
-        // Apply yaw rotation (around the z-axis) to align the point with the camera's orientation.
-        let (rot_x, rot_y, rot_z) = (
-            delta_x * cos_yaw - delta_z * sin_yaw,
-            delta_y,
-            delta_x * sin_yaw + delta_z * cos_yaw,
-        );
-
-        // Apply pitch rotation (around the y-axis) to adjust the point for the camera's tilt.
-        let (pitch_x, pitch_y, pitch_z) = (
-            rot_x,
-            rot_y * cos_pitch - rot_z * sin_pitch,
-            rot_y * sin_pitch + rot_z * cos_pitch,
+        // Apply yaw rotation (around the vertical axis) to align the point with the camera's orientation.
+        let rotated = Point::new(
+            delta.x * cos_yaw - delta.z * sin_yaw,
+            delta.y,
+            delta.x * sin_yaw + delta.z * cos_yaw,
         );
 
-        // Apply roll rotation (around the x-axis) to finalize the point's transformation.
-        let (roll_x, roll_y, roll_z) = (
-            pitch_x * cos_roll - pitch_y * sin_roll,
-            pitch_x * sin_roll + pitch_y * cos_roll,
-            pitch_z,
+        // Apply pitch rotation (around the sideways axis) to adjust the point for the camera's tilt.
+        let pitched = Point::new(
+            rotated.x,
+            rotated.y * cos_pitch - rotated.z * sin_pitch,
+            rotated.y * sin_pitch + rotated.z * cos_pitch,
         );
 
-        Point::new(roll_x, roll_y, roll_z)
+        // Apply roll rotation (around the viewing axis) to finalize the point's transformation.
+        Point::new(
+            pitched.x * cos_roll - pitched.y * sin_roll,
+            pitched.x * sin_roll + pitched.y * cos_roll,
+            pitched.z,
+        )
     }
 
     // Converts a 3D point in camera space to 2D screen coordinates for rendering.
     fn camera_to_screen(&self, camera_point: &Point) -> screen::Point {
+        // Return the final screen coordinates rounded to the nearest pixel.
+        let (screen_x, screen_y) = self.camera_to_screen_exact(camera_point);
+        screen::Point::new(screen_x.round() as i32, screen_y.round() as i32)
+    }
+
+    // Converts a 3D point in camera space to unrounded 2D screen coordinates, which stay accurate far outside the screen.
+    fn camera_to_screen_exact(&self, camera_point: &Point) -> (f64, f64) {
+        let (x, y, z) = (camera_point.x as f64, camera_point.y as f64, camera_point.z as f64);
+        let viewport_distance = self.viewport_distance as f64;
+
         // Project the 3D point onto the 2D viewport.
-        let projected_x = camera_point.x * self.viewport_distance / camera_point.z;
-        let projected_y = camera_point.y * self.viewport_distance / camera_point.z;
+        let projected_x = x * viewport_distance / z;
+        let projected_y = y * viewport_distance / z;
 
         // Calculate the viewport dimensions based on the camera's FOV and the screen's aspect ratio.
-        let viewport_width = 2.0 * self.viewport_distance * (self.viewport_fov / 2.0).tan();
-        let viewport_height = (self.screen.height as f32 / self.screen.width as f32) * viewport_width;
+        let viewport_width = 2.0 * viewport_distance * (self.viewport_fov as f64 / 2.0).tan();
+        let viewport_height = (self.screen.height as f64 / self.screen.width.max(1) as f64) * viewport_width;  // Avoid dividing by zero on an empty screen.
 
         // Convert the projected coordinates into screen coordinates.
-        let screen_x = (projected_x / viewport_width + 0.5) * self.screen.width as f32;
-        let screen_y = (1.0 - (projected_y / viewport_height + 0.5)) * self.screen.height as f32;
+        let screen_x = (projected_x / viewport_width + 0.5) * self.screen.width as f64;
+        let screen_y = (1.0 - (projected_y / viewport_height + 0.5)) * self.screen.height as f64;
+        (screen_x, screen_y)
+    }
 
-        // Return the final screen coordinates rounded to the nearest pixel.
-        screen::Point::new(screen_x.round() as i32, screen_y.round() as i32)
+    // Converts a screen pixel into a ray in world space, returned as its origin (the camera's position) and unit direction.
+    // Every world point on the ray projects back onto the pixel, which makes this the basis for picking things on screen.
+    pub fn screen_to_world_ray(&self, screen_point: &screen::Point) -> (Point, Point) {
+        // Undo the conversion from viewport to screen coordinates, in the same way camera_to_screen does it.
+        let viewport_width = 2.0 * self.viewport_distance * (self.viewport_fov / 2.0).tan();
+        let viewport_height = (self.screen.height as f32 / self.screen.width.max(1) as f32) * viewport_width;
+        let projected_x = (screen_point.x as f32 / self.screen.width.max(1) as f32 - 0.5) * viewport_width;
+        let projected_y = (0.5 - screen_point.y as f32 / self.screen.height.max(1) as f32) * viewport_height;
+
+        // The ray passes through the projected point on the viewport, which lies at the viewport distance in camera space.
+        let direction = self.direction_to_world(&Point::new(projected_x, projected_y, self.viewport_distance));
+        (self.coordinates, direction.normalized())
+    }
+
+    // Finds the point of a 3D model shown at a screen pixel, or None if there is nothing there.
+    // For models with faces, this is the nearest place the pixel's ray hits a triangle. Otherwise it is the nearest vertex.
+    // Geometry behind the viewport or hidden by the clip plane can't be picked.
+    pub fn pick(&self, model: &model::Model, screen_point: &screen::Point) -> Option<Point> {
+        if !model.triangles.is_empty() {
+            let (origin, direction) = self.screen_to_world_ray(screen_point);
+            return model.triangles
+                .iter()
+                .filter_map(|triangle| ray_triangle_distance(&origin, &direction, triangle.map(|index| model.model_to_world(&model.points[index]))))
+                .map(|distance| origin + direction * distance)
+                .filter(|hit| self.world_to_camera(hit).z >= self.viewport_distance && !self.is_clipped(hit))
+                .min_by(|a, b| self.world_to_camera(a).z.total_cmp(&self.world_to_camera(b).z));
+        }
+        self.nearest_vertex(model, screen_point)
+    }
+
+    // Finds the vertex (or end of an edge) of a 3D model drawn closest to a screen pixel, within a few pixels of it,
+    // preferring the nearest to the camera, or None if there is none that close.
+    // Vertices behind the viewport or hidden by the clip plane are left out.
+    pub fn nearest_vertex(&self, model: &model::Model, screen_point: &screen::Point) -> Option<Point> {
+        model.points
+            .iter()
+            .chain(model.edges.iter().flat_map(|(start, end)| [start, end]))
+            .filter_map(|point| {
+                let world_point = model.model_to_world(point);
+                let camera_point = self.world_to_camera(&world_point);
+                if camera_point.z < self.viewport_distance || self.is_clipped(&world_point) {
+                    return None;
+                }
+                let (x, y) = self.camera_to_screen_exact(&camera_point);
+                let distance = (x - screen_point.x as f64).hypot(y - screen_point.y as f64);
+                (distance <= PICK_RADIUS).then_some((distance, camera_point.z, world_point))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)))
+            .map(|(_, _, world_point)| world_point)
     }
 
     // Renders the points of a 3D model onto the screen.
@@ -116,29 +557,420 @@ impl Camera {
         }
     }
 
-    // Renders the edges of a 3D model by connecting its points with lines.
+    // Renders the points of a 3D model, leaving out the points hidden behind nearer ones so a dense cloud looks like a solid surface.
+    // Without faces to hide them, every point covers a small disc of depth, and only the points nearest within their own pixel are drawn.
+    pub fn plot_model_points_occluded(&mut self, model: &model::Model) {
+        let mut splats = Vec::<(screen::Point, f32)>::with_capacity(model.point_count());
+        for point in model.points.iter() {
+            let world_point = model.model_to_world(point);
+            let camera_point = self.world_to_camera(&world_point);
+            if camera_point.z >= self.viewport_distance && !self.is_clipped(&world_point) {
+                splats.push((self.camera_to_screen(&camera_point), camera_point.z));
+            }
+        }
+
+        // Record the depth of every splat first, so that the order of the points doesn't matter.
+        for (point, depth) in splats.iter() {
+            self.screen.splat_depth(point, POINT_SPLAT_RADIUS, *depth);
+        }
+
+        // A splat is wider in the world the farther away it is, so the depth allowed between neighbors grows with depth too.
+        let pixel_size = 2. * (self.viewport_fov / 2.).tan() / self.screen.width.max(1) as f32;
+        let tolerance = 1. + POINT_SURFACE_SLOPE * POINT_SPLAT_RADIUS as f32 * pixel_size;
+        for (point, depth) in splats.iter() {
+            if self.screen.depth_at(point).is_some_and(|nearest| *depth <= nearest * tolerance) {
+                self.screen.write(true, point);
+            }
+        }
+    }
+
+    // Renders the points of a 3D model as small dots, so they stand out when drawn on top of the edges.
+    pub fn plot_model_vertex_markers(&mut self, model: &model::Model) {
+        for point in model.points.iter() {
+            let world_point = model.model_to_world(point);
+            let camera_point = self.world_to_camera(&world_point);
+            if camera_point.z >= self.viewport_distance && !self.is_clipped(&world_point) {
+                self.screen.dot(&self.camera_to_screen(&camera_point), VERTEX_MARKER_RADIUS);
+            }
+        }
+    }
+
+    // Renders the edges of a 3D model by connecting its points with lines, along with its standalone point elements.
+    // Models with more edges than the edge budget are decimated by only drawing every few edges.
     pub fn plot_model_edges(&mut self, model: &model::Model) {
-        for edge in model.edges.iter() {
+        // When culling back faces, leave out the edges whose triangles are all turned away. This needs `edge_triangles`,
+        // without which every edge is drawn. Edges that aren't sides of triangles, such as lines, are always drawn.
+        let back_faces = if model.edge_triangles.len() == model.edges.len() { self.back_faces(model) } else { Vec::new() };
+        let culled = |index: usize| match model.edge_triangles.get(index) {
+            _ if back_faces.is_empty() => false,
+            Some([Some(first), second]) => back_faces[*first] && second.is_none_or(|second| back_faces[second]),
+            _ => false,
+        };
+
+        let budget = self.edge_budget.map(|budget| budget.max(1)).filter(|&budget| budget < model.edge_count());
+        let mut culled_count = 0;
+        let plotted = match (budget, self.edge_priority) {
+            (Some(budget), EdgePriority::Length | EdgePriority::Silhouette) => {
+                let indices = self.most_important_edges(model, budget);
+                for &index in indices.iter() {
+                    if culled(index) { culled_count += 1 } else { self.plot_model_edge(model, index) }
+                }
+                indices.len()
+            }
+            (budget, _) => {
+                let stride = budget.map_or(1, |budget| model.edge_count().div_ceil(budget));
+                for index in (0..model.edge_count()).step_by(stride) {
+                    if culled(index) { culled_count += 1 } else { self.plot_model_edge(model, index) }
+                }
+                model.edge_count().div_ceil(stride)
+            }
+        };
+        self.stats.edges_considered += model.edge_count() - plotted + culled_count;  // Edges left out by the budget or culled were still considered.
+        self.screen.pen = None;
+        for &index in model.point_indices.iter() {
+            self.write(true, &model.model_to_world(&model.points[index]));
+        }
+    }
+
+    // Renders one edge of a 3D model in its material's color.
+    fn plot_model_edge(&mut self, model: &model::Model, index: usize) {
+        let (start, end) = model.edges[index];
+        self.screen.pen = terminal_color(model.edge_color(index));
+        self.edge(&model.model_to_world(&start), &model.model_to_world(&end));
+    }
+
+    // Picks the indices of the edges that matter most to the shape of a 3D model, keeping the given number of them.
+    // Edges are ranked by their length on screen, so that small details are dropped before the large ones.
+    // With silhouette priority, the edges between faces turned towards and away from the camera, and the edges on the border of an open mesh,
+    // come first, keeping the outline intact. This needs `edge_triangles`, without which edges are only ranked by length.
+    fn most_important_edges(&self, model: &model::Model, budget: usize) -> Vec<usize> {
+        let facing: Vec<bool> = if self.edge_priority == EdgePriority::Silhouette && model.edge_triangles.len() == model.edges.len() {
+            model.triangles
+                .iter()
+                .map(|triangle| {
+                    let [a, b, c] = triangle.map(|index| model.model_to_world(&model.points[index]));
+                    (b - a).cross(&(c - a)).dot(&(self.coordinates - a)) > 0.
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let on_silhouette = |index: usize| match model.edge_triangles.get(index) {
+            _ if facing.is_empty() => false,
+            Some([Some(first), Some(second)]) => facing[*first] != facing[*second],
+            Some([Some(_), None]) => true,
+            _ => false,
+        };
+
+        // Ends behind the viewport are projected as if they were on it, which is close enough for ranking.
+        let project = |point: &Point| {
+            let camera_point = self.world_to_camera(&model.model_to_world(point));
+            self.camera_to_screen_exact(&Point::new(camera_point.x, camera_point.y, camera_point.z.max(self.viewport_distance)))
+        };
+        let screen_length = |(start, end): &(Point, Point)| {
+            let (start, end) = (project(start), project(end));
+            ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt()
+        };
+
+        let mut ranked: Vec<(bool, f64, usize)> = model.edges
+            .iter()
+            .enumerate()
+            .map(|(index, edge)| (on_silhouette(index), screen_length(edge), index))
+            .collect();
+        ranked.select_nth_unstable_by(budget, |a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+        ranked.truncate(budget);
+        ranked.into_iter().map(|(_, _, index)| index).collect()
+    }
+
+    // Renders the boundary and non-manifold triangle sides of a 3D model in their highlight colors, over its edges drawn in the default color.
+    // Boundary edges are shared by one face, and non-manifold edges by more than two.
+    pub fn plot_model_manifold_highlights(&mut self, model: &model::Model) {
+        let counts = model.edge_face_counts();
+        for (&(start, end), &count) in counts.iter() {
+            self.screen.pen = match count {
+                1 => Some(BOUNDARY_EDGE_COLOR),
+                2 => continue,
+                _ => Some(NON_MANIFOLD_EDGE_COLOR),
+            };
             self.edge(
-                &model.model_to_world(&edge.0),
-                &model.model_to_world(&edge.1),
+                &model.model_to_world(&model.points[start]),
+                &model.model_to_world(&model.points[end]),
             );
         }
+        self.screen.pen = None;
+    }
+
+    // Renders a marker for each light, out from the center of a 3D model in the light's direction, like a sun around it.
+    // The default lights following the camera are behind it, so they get no markers.
+    pub fn plot_light_markers(&mut self, model: &model::Model) {
+        let (min, max) = model.world_bounds();
+        let (center, radius) = ((min + max) * 0.5, (max - min).length() / 2.);
+        self.screen.pen = Some(LIGHT_MARKER_COLOR);
+        for light in self.lights.clone().iter() {
+            let world_point = center + light.direction * (radius * LIGHT_MARKER_DISTANCE).max(self.viewport_distance);
+            let camera_point = self.world_to_camera(&world_point);
+            if camera_point.z >= self.viewport_distance {
+                self.screen.dot(&self.camera_to_screen(&camera_point), LIGHT_MARKER_RADIUS);
+            }
+        }
+        self.screen.pen = None;
+    }
+
+    // Renders the edges of a 3D model's axis-aligned bounding box in its own color.
+    pub fn plot_bounding_box(&mut self, model: &model::Model) {
+        self.screen.pen = Some(BOUNDING_BOX_COLOR);
+        for (start, end) in model.world_bounds_edges().iter() {
+            self.edge(start, end);
+        }
+        self.screen.pen = None;
+    }
+
+    // Renders the parts of a 3D model chosen by the display mode.
+    // Edges are drawn as face outlines when faces have debug colors, so that each face shows up in its own color.
+    pub fn plot_model(&mut self, model: &model::Model, display_mode: DisplayMode) {
+        if display_mode != DisplayMode::Points {
+            match self.face_color_seed {
+                Some(_) => self.plot_model_face_outlines(model),
+                None => self.plot_model_edges(model),
+            }
+        }
+        match display_mode {
+            DisplayMode::Edges => {}
+            DisplayMode::Points => self.plot_model_points(model),
+            DisplayMode::Both => self.plot_model_vertex_markers(model),
+        }
+    }
+
+    // Draws a whole frame of a 3D model: sizes and clears the screen, draws the model as the options ask, and outputs the result.
+    // Frames are drawn on the terminal, or returned as text when the options give a headless size.
+    // When the terminal is too small, only a message saying so is drawn.
+    // Returns what was drawn along with the text, which is also kept in `stats` until the next frame.
+    // To hide parts of the model or explode it, draw the copy made by Model::without_hidden or Model::exploded, which is too slow to make every frame.
+    pub fn render_frame(&mut self, model: &model::Model, options: &RenderOptions) -> (Option<String>, RenderStats) {
+        self.stats = RenderStats::default();
+        let text = match options.pixel_mode {
+            screen::PixelMode::Block => self.render_frame_with::<screen::BlockPixel>(model, options),
+            screen::PixelMode::Braille => self.render_frame_with::<screen::BrailePixel>(model, options),
+            screen::PixelMode::Sextant => self.render_frame_with::<screen::SextantPixel>(model, options),
+            screen::PixelMode::Ascii => self.render_frame_with::<screen::AsciiPixel>(model, options),
+        };
+        self.stats.pixels_written = self.screen.pixels_written;
+        (text, self.stats)
+    }
+
+    // Draws a whole frame of a 3D model with a specific pixel type, as described for render_frame.
+    fn render_frame_with<PixelType: screen::Pixel>(&mut self, model: &model::Model, options: &RenderOptions) -> Option<String> {
+        match options.headless {
+            Some((columns, rows, _)) => self.screen.fit_to_cells::<PixelType>(columns, rows),
+            None => {
+                if !self.screen.fit_to_terminal::<PixelType>() {
+                    self.screen.render_too_small();
+                    return None;
+                }
+            }
+        }
+
+        self.screen.clear();
+        if options.filled_faces || options.shading {
+            self.plot_model_faces(model, options.filled_faces);
+        }
+        if !options.filled_faces {
+            if options.occlude_points && options.display_mode == DisplayMode::Points {
+                self.plot_model_points_occluded(model);
+            } else {
+                self.plot_model(model, options.display_mode);
+            }
+        }
+        if options.manifold_edges {
+            self.plot_model_manifold_highlights(model);
+        }
+        if options.bounding_box {
+            self.plot_bounding_box(model);
+        }
+        if options.light_markers {
+            self.plot_light_markers(model);
+        }
+
+        match options.headless {
+            Some((_, _, format)) => Some(self.screen.to_text::<PixelType>(format)),
+            None => {
+                self.screen.render::<PixelType>();
+                None
+            }
+        }
+    }
+
+    // Returns the color to draw a face in: its debug color when face colors are seeded, or otherwise its material's color.
+    fn face_color(&self, model: &model::Model, index: usize) -> Option<Color> {
+        match self.face_color_seed {
+            Some(seed) => Some(debug_face_color(seed, index)),
+            None => terminal_color(model.triangle_color(index)),
+        }
+    }
+
+    // Renders the sides of each triangle of a 3D model in the triangle's color, along with the model's other edges.
+    // With seeded face colors this tells adjacent faces apart, as every face is outlined in its own color.
+    pub fn plot_model_face_outlines(&mut self, model: &model::Model) {
+        self.plot_model_edges(model);
+
+        for (index, triangle) in model.triangles.iter().enumerate() {
+            self.screen.pen = self.face_color(model, index);
+            for side in 0..3 {
+                self.edge(
+                    &model.model_to_world(&model.points[triangle[side]]),
+                    &model.model_to_world(&model.points[triangle[(side + 1) % 3]]),
+                );
+            }
+        }
+        self.screen.pen = None;
+    }
+
+    // Renders the triangles of a 3D model as shaded faces, leaving out the back faces while they are culled.
+    // Filled faces hide whatever is behind them, while the others are only drawn from back to front, for drawing edges over them.
+    pub fn plot_model_faces(&mut self, model: &model::Model, filled: bool) {
+        if filled {
+            self.plot_model_faces_filled(model);
+        } else {
+            self.plot_model_faces_dithered(model);
+        }
+    }
+
+    // Renders the triangles of a 3D model as flat-shaded faces, using dithering to represent each face's intensity.
+    pub fn plot_model_faces_dithered(&mut self, model: &model::Model) {
+        let mut triangles = self.camera_triangles(model);
+
+        // Draw the farthest triangles first so that nearer ones are painted over them.
+        triangles.sort_by(|(_, a), (_, b)| {
+            let depth_a = a[0].z + a[1].z + a[2].z;
+            let depth_b = b[0].z + b[1].z + b[2].z;
+            depth_b.total_cmp(&depth_a)
+        });
+
+        let lights = self.camera_lights();
+        for (index, triangle) in triangles.iter() {
+            let intensity = self.face_intensity(model, *index, triangle, &lights);
+            let projected = triangle.map(|point| self.camera_to_screen(&point));
+            self.screen.pen = self.face_color(model, *index);
+            self.screen.fill_dithered(&projected, intensity);
+        }
+        self.screen.pen = None;
+    }
+
+    // Renders the triangles of a 3D model as filled faces with dithered flat shading, hiding whatever is behind them.
+    // Unlike plot_model_faces_dithered, the faces are kept in front of each other pixel by pixel with the screen's depth buffer,
+    // which stays right for faces that cut through each other and doesn't need them sorted.
+    pub fn plot_model_faces_filled(&mut self, model: &model::Model) {
+        let triangles = self.camera_triangles(model);
+        let lights = self.camera_lights();
+        for (index, triangle) in triangles.iter() {
+            let intensity = self.face_intensity(model, *index, triangle, &lights);
+            let projected = triangle.map(|point| self.camera_to_screen(&point));
+            self.screen.pen = self.face_color(model, *index);
+            self.screen.fill_triangle(&projected, triangle.map(|point| point.z), intensity);
+        }
+        self.screen.pen = None;
+    }
+
+    // Transforms each triangle of a 3D model into camera space, skipping any that cross the viewport or reach past the clip plane,
+    // and any back faces being culled. Each triangle keeps its index so that a cached face normal can be looked up for it.
+    fn camera_triangles(&mut self, model: &model::Model) -> Vec<(usize, [Point; 3])> {
+        let back_faces = self.back_faces(model);
+        let triangles: Vec<(usize, [Point; 3])> = model.triangles
+            .iter()
+            .map(|triangle| triangle.map(|index| model.model_to_world(&model.points[index])))
+            .enumerate()
+            .filter(|(index, _)| !back_faces.get(*index).copied().unwrap_or(false))
+            .filter(|(_, triangle)| !triangle.iter().any(|point| self.is_clipped(point)))
+            .map(|(index, triangle)| (index, triangle.map(|point| self.world_to_camera(&point))))
+            .filter(|(_, triangle)| triangle.iter().all(|point| point.z >= self.viewport_distance))
+            .collect();
+        self.stats.triangles_considered += model.triangle_count();
+        self.stats.triangles_drawn += triangles.len();
+        triangles
+    }
+
+    // Flags the triangles of a 3D model that are turned away from the camera, for culling back faces.
+    // Which way a triangle faces is found from the sign of its cross product in camera space, where the camera is at the origin,
+    // so it doesn't depend on the screen's resolution. Nothing is flagged unless culling is on and the model is a closed mesh wound
+    // consistently, since the back of an open or inconsistently wound mesh can be seen and would leave holes if culled.
+    // The winding is looked up in the model's cache rather than found again for every frame, so it needs `cache_edge_triangles`.
+    fn back_faces(&self, model: &model::Model) -> Vec<bool> {
+        if !self.cull_backfaces {
+            return Vec::new();
+        }
+        let outward = match model.winding {
+            Some(outward) => outward,
+            None => return Vec::new(),
+        };
+        model.triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|index| self.world_to_camera(&model.model_to_world(&model.points[index])));
+                let towards_camera = (b - a).cross(&(c - a)).dot(&a) < 0.;
+                towards_camera != outward
+            })
+            .collect()
+    }
+
+    // Returns the lights in camera space, where faces are lit and the default lights are already given.
+    fn camera_lights(&self) -> Vec<Light> {
+        if self.lights.is_empty() {
+            default_lights()
+        } else {
+            self.lights.iter().map(|light| Light { direction: self.direction_to_camera(&light.direction), ..*light }).collect()
+        }
+    }
+
+    // Adds up the light a face in camera space gets from every light, along with the ambient light, from 0 to 1.
+    fn face_intensity(&self, model: &model::Model, index: usize, triangle: &[Point; 3], lights: &[Light]) -> f32 {
+        let normal = model.face_normals.get(index).map(|normal| self.direction_to_camera(normal));
+        let diffuse: f32 = lights
+            .iter()
+            .map(|light| light.intensity * match &normal {
+                Some(normal) => normal.dot(&light.direction).abs(),
+                None => flat_intensity(triangle, &light.direction),
+            })
+            .sum();
+        (diffuse + self.ambient).clamp(0., 1.)
     }
 
     // Renders a single 3D point by converting it to camera and then screen coordinates.
     pub fn write(&mut self, val: bool, point: &Point) {
         let camera_point = self.world_to_camera(point);
-        if camera_point.z >= self.viewport_distance {
+        if camera_point.z >= self.viewport_distance && !self.is_clipped(point) {
             self.screen.write(val, &self.camera_to_screen(&camera_point));
         }
     }
 
+    // Checks whether a world point is hidden by the clip plane.
+    fn is_clipped(&self, point: &Point) -> bool {
+        match &self.clip_plane {
+            Some(plane) => plane.distance(point) > 0.,
+            None => false,
+        }
+    }
+
     // Renders an edge (a line) between two points, clipping if necessary.
     pub fn edge(&mut self, start: &Point, end: &Point) {
+        let (mut start, mut end) = (*start, *end);
+        self.stats.edges_considered += 1;
+
+        // Cut the edge where it crosses the clip plane, dropping it entirely if it lies on the hidden side.
+        if let Some(plane) = self.clip_plane {
+            let (start_distance, end_distance) = (plane.distance(&start), plane.distance(&end));
+            if start_distance > 0. && end_distance > 0. {
+                return;
+            }
+            if start_distance > 0. || end_distance > 0. {
+                let lambda = start_distance / (start_distance - end_distance);
+                let crossing = start + (end - start) * lambda;
+                if start_distance > 0. { start = crossing } else { end = crossing }
+            }
+        }
+
         // Convert both points to camera space.
-        let camera_start = self.world_to_camera(start);
-        let camera_end = self.world_to_camera(end);
+        let camera_start = self.world_to_camera(&start);
+        let camera_end = self.world_to_camera(&end);
 
         // Check if any point is behind the viewport and needs to be clipped.
         let clip_start = camera_start.z < self.viewport_distance;
@@ -151,10 +983,7 @@ impl Camera {
 
         // If neither point is behind the viewport, draw the line between them.
         if !clip_start && !clip_end {
-            self.screen.line(
-                &self.camera_to_screen(&camera_start),
-                &self.camera_to_screen(&camera_end),
-            );
+            self.segment(&camera_start, &camera_end);
             return;
         }
 
@@ -167,24 +996,265 @@ impl Camera {
 
         // Calculate the point where the clipped point intersects the viewport.
         let distance_to_clip = self.viewport_distance - clipped.z;
-        let (delta_x, delta_y, delta_z) = (
-            unclipped.x - clipped.x,
-            unclipped.y - clipped.y,
-            unclipped.z - clipped.z,
-        );
-        let lambda = distance_to_clip / delta_z;
+        let delta = unclipped - clipped;
+        let lambda = distance_to_clip / delta.z;
 
-        // Compute the new clipped point at the intersection.
-        let new_clipped = Point::new(
-            lambda * delta_x + clipped.x,
-            lambda * delta_y + clipped.y,
-            self.viewport_distance,
-        );
+        // Compute the new clipped point at the intersection, placing it exactly on the viewport despite rounding.
+        let mut new_clipped = clipped + delta * lambda;
+        new_clipped.z = self.viewport_distance;
 
         // Draw the clipped line from the new clipped point to the unclipped point.
-        self.screen.line(
-            &self.camera_to_screen(&new_clipped),
-            &self.camera_to_screen(&unclipped),
-        );
+        self.segment(&new_clipped, &unclipped);
+    }
+
+    // Draws a line between two camera-space points in front of the viewport, fading it with distance if fog is enabled.
+    // The line is clipped to the screen before being rounded to pixels, as an end close to the viewport can project far outside of it.
+    fn segment(&mut self, camera_start: &Point, camera_end: &Point) {
+        let (exact_start, exact_end) = (self.camera_to_screen_exact(camera_start), self.camera_to_screen_exact(camera_end));
+        let (t_enter, t_exit) = match self.screen.clip_fractions(exact_start, exact_end) {
+            Some(fractions) => fractions,
+            None => return,
+        };
+        self.stats.edges_drawn += 1;
+        let start = screen::Point::along(exact_start, exact_end, t_enter);
+        let end = screen::Point::along(exact_start, exact_end, t_exit);
+
+        match self.fog {
+            Some(fog) => {
+                // Fade the visible part of the line between the fog intensities at its original ends.
+                let (start_intensity, end_intensity) = (fog_intensity(camera_start.z, fog), fog_intensity(camera_end.z, fog));
+                let intensity_at = |fraction: f64| start_intensity + (end_intensity - start_intensity) * fraction as f32;
+                self.screen.line_faded(&start, &end, intensity_at(t_enter), intensity_at(t_exit), self.line_width)
+            }
+            None => self.screen.line_thick(&start, &end, self.line_width),
+        }
+    }
+}
+
+// Rotates a vector around a unit axis through the origin by an angle in radians, using Rodrigues' rotation formula.
+fn rotate_around(vector: &Point, axis: &Point, angle: f32) -> Point {
+    let (sin, cos) = angle.sin_cos();
+    *vector * cos + axis.cross(vector) * sin + *axis * (axis.dot(vector) * (1. - cos))
+}
+
+// Finds how far along a ray, from its origin in its unit direction, it hits a triangle from either side, or None if it misses.
+// This is the Möller-Trumbore intersection test.
+fn ray_triangle_distance(origin: &Point, direction: &Point, [a, b, c]: [Point; 3]) -> Option<f32> {
+    let (ab, ac) = (b - a, c - a);
+    let p = direction.cross(&ac);
+    let determinant = ab.dot(&p);
+    if determinant == 0. {
+        return None;  // The ray runs along the plane of the triangle, or the triangle is degenerate.
+    }
+
+    // Barycentric coordinates of the hit, which are all between 0 and 1 inside the triangle.
+    let to_origin = *origin - a;
+    let u = to_origin.dot(&p) / determinant;
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(&ab);
+    let v = direction.dot(&q) / determinant;
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+
+    let distance = ac.dot(&q) / determinant;
+    (distance > 0.).then_some(distance)
+}
+
+// Computes how visible (0 to 1) something at a given camera-space depth is through fog spanning (start, end) distances.
+pub fn fog_intensity(depth: f32, (start, end): (f32, f32)) -> f32 {
+    if end <= start {
+        return if depth < end { 1. } else { 0. };
+    }
+    1. - ((depth - start) / (end - start)).clamp(0., 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A camera looking at the world origin from a few units away, as the viewer starts out.
+    fn test_camera() -> Camera {
+        Camera::new(Point::new(0., 0., -3.), 0., 0., 0., 0.1, 1.2)
+    }
+
+    // Options for rendering small plain text frames of shaded faces.
+    fn shaded_options() -> RenderOptions {
+        let mut options = RenderOptions::new();
+        options.shading = true;
+        options.headless = Some((40, 20, screen::OutputFormat::Plain));
+        options
+    }
+
+    // Builds a closed sphere with its winding and edge triangles cached, as the viewer does when loading models.
+    fn closed_sphere() -> model::Model {
+        let mut model = model::Model::new_icosphere(1., 2, Point::new(0., 0., 0.));
+        model.cache_edge_triangles();
+        model
+    }
+
+    // Golden frame of the cube demo, checked in next to this file. Run the tests with UPDATE_GOLDEN_FRAMES set to rewrite it
+    // after a change that is meant to change how the cube is drawn, and look over the new frame before committing it.
+    const GOLDEN_CUBE_FRAME: &str = include_str!("golden/cube_braille.txt");
+
+    #[test]
+    fn tiny_frames_render_without_dividing_by_zero() {
+        let model = model::Model::new_cube(1., Point::new(0., 0., 0.));
+        let mut camera = test_camera();
+        for (columns, rows) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let mut options = RenderOptions::new();
+            options.shading = true;
+            options.headless = Some((columns, rows, screen::OutputFormat::Plain));
+            let (text, stats) = camera.render_frame(&model, &options);
+            if columns == 0 || rows == 0 {
+                assert!(text.unwrap().chars().all(|character| character == '\n'));
+                assert_eq!(stats.pixels_written, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn center_ray_points_along_the_forward_axis() {
+        let mut camera = test_camera();
+        camera.set_angles(40_f32.to_radians(), -25_f32.to_radians(), 0.);
+        camera.screen.resize(80, 48);
+
+        let (origin, direction) = camera.screen_to_world_ray(&screen::Point::new(40, 24));
+        assert_eq!((origin.x, origin.y, origin.z), (0., 0., -3.));
+        let along = camera.world_to_camera(&(origin + direction * 5.));
+        assert!(along.x.abs() < 1e-5 && along.y.abs() < 1e-5);
+        assert!((along.z - 5.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cube_renders_to_a_string_of_the_requested_size() {
+        let model = model::Model::new_cube(1., Point::new(0., 0., 0.));
+        let mut camera = test_camera();
+        let mut options = RenderOptions::new();
+        options.headless = Some((30, 12, screen::OutputFormat::Plain));
+
+        let frame = camera.render_frame(&model, &options).0.unwrap();
+        let rows: Vec<&str> = frame.lines().collect();
+        assert_eq!(rows.len(), 12);
+        assert!(rows.iter().all(|row| row.chars().count() == 30));
+        assert!(frame.chars().any(|c| c != ' ' && c != '\n'));
+        assert_eq!(camera.render_frame(&model, &options).0.unwrap(), frame);
+    }
+
+    #[test]
+    fn occluded_points_hide_the_far_side_of_a_cloud() {
+        let model = model::Model::new_icosphere(1., 3, Point::new(0., 0., 0.));
+        let mut camera = test_camera();
+        let mut options = RenderOptions::new();
+        options.display_mode = DisplayMode::Points;
+        options.headless = Some((60, 30, screen::OutputFormat::Plain));
+
+        let (_, all) = camera.render_frame(&model, &options);
+        options.occlude_points = true;
+        let (_, occluded) = camera.render_frame(&model, &options);
+        // Roughly the half of the sphere facing the camera is left.
+        assert_eq!(all.pixels_written, model.points.len());
+        assert!(occluded.pixels_written * 10 > all.pixels_written * 4 && occluded.pixels_written * 10 < all.pixels_written * 7);
+    }
+
+    #[test]
+    fn cube_draws_no_more_than_it_considers() {
+        let model = model::Model::new_cube(1., Point::new(0., 0., 0.));
+        let mut camera = test_camera();
+        let mut options = RenderOptions::new();
+        options.bounding_box = true;
+        options.headless = Some((40, 20, screen::OutputFormat::Plain));
+
+        let (_, stats) = camera.render_frame(&model, &options);
+        assert!(stats.edges_considered > model.edges.len());
+        assert!(0 < stats.edges_drawn && stats.edges_drawn <= stats.edges_considered);
+        assert!(stats.pixels_written > 0);
+
+        // With the camera inside the cube, the edges of the face behind it can't be drawn.
+        camera.coordinates = Point::new(0., 0., 0.);
+        let (_, stats) = camera.render_frame(&model, &options);
+        assert!(stats.edges_drawn < stats.edges_considered);
+    }
+
+    #[test]
+    fn cube_matches_its_golden_frame() {
+        let model = model::Model::new_cube(1., Point::new(0., 0., 0.));
+        let mut camera = test_camera();
+        camera.set_angles(30_f32.to_radians(), 20_f32.to_radians(), 0.);
+        camera.orbit(&Point::new(0., 0., 0.), 2.5);
+        let mut options = RenderOptions::new();
+        options.pixel_mode = screen::PixelMode::Braille;
+        options.headless = Some((40, 16, screen::OutputFormat::Plain));
+        let frame = camera.render_frame(&model, &options).0.unwrap() + "\n";
+
+        if env::var_os("UPDATE_GOLDEN_FRAMES").is_some() {
+            fs::write(path::Path::new(file!()).with_file_name("golden").join("cube_braille.txt"), &frame).unwrap();
+        } else {
+            assert!(frame == GOLDEN_CUBE_FRAME, "The cube no longer matches its golden frame. Got:\n{}", frame);
+        }
+    }
+
+    #[test]
+    fn culling_leaves_out_the_far_side_of_closed_meshes() {
+        let model = closed_sphere();
+        let mut camera = test_camera();
+        let (_, all) = camera.render_frame(&model, &shaded_options());
+        camera.cull_backfaces = true;
+        let (_, culled) = camera.render_frame(&model, &shaded_options());
+
+        assert!(culled.triangles_drawn > all.triangles_drawn * 3 / 10);
+        assert!(culled.triangles_drawn < all.triangles_drawn * 6 / 10);
+        assert!(culled.edges_drawn < all.edges_drawn);
+    }
+
+    #[test]
+    fn culling_works_for_inward_wound_meshes() {
+        let mut inward = closed_sphere();
+        for triangle in inward.triangles.iter_mut() {
+            triangle.swap(1, 2);
+        }
+        inward.cache_edge_triangles();
+        assert_eq!(inward.winding, Some(false));
+
+        let mut camera = test_camera();
+        camera.cull_backfaces = true;
+        let (_, outward) = camera.render_frame(&closed_sphere(), &shaded_options());
+        let (_, inward) = camera.render_frame(&inward, &shaded_options());
+        assert_eq!(inward.triangles_drawn, outward.triangles_drawn);
+    }
+
+    #[test]
+    fn culling_is_a_no_op_for_open_meshes() {
+        let mut open = closed_sphere();
+        open.triangles.pop();
+        open.cache_edge_triangles();
+        assert_eq!(open.winding, None);
+
+        let mut camera = test_camera();
+        let (all, _) = camera.render_frame(&open, &shaded_options());
+        camera.cull_backfaces = true;
+        let (culled, _) = camera.render_frame(&open, &shaded_options());
+        assert_eq!(culled, all);
+    }
+
+    #[test]
+    fn culling_keeps_filled_faces_in_front_as_they_are() {
+        let mut model = closed_sphere();
+        model.cache_normals();
+        let mut options = RenderOptions::new();
+        options.filled_faces = true;
+        options.headless = Some((40, 20, screen::OutputFormat::Plain));
+
+        let mut camera = Camera::new(Point::new(0.3, 0.2, -3.), 0., 0., 0., 0.1, 1.2);
+        let (all, _) = camera.render_frame(&model, &options);
+        camera.cull_backfaces = true;
+        let (culled, _) = camera.render_frame(&model, &options);
+
+        // Only the outline can change, where the edges of back faces peek out from behind the front ones.
+        let (all, culled) = (all.unwrap(), culled.unwrap());
+        let changed = all.chars().zip(culled.chars()).filter(|(all, culled)| all != culled).count();
+        assert!(changed < 10);
     }
 }
@@ -0,0 +1,65 @@
+use std::*;
+use time::{Duration, Instant, SystemTime};
+
+// How often the file's modification time is checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+// How long the file must stay unchanged after a write before it is reported, so that a save made of several writes only reloads once.
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(250);
+
+// Watches a file on disk by polling its modification time.
+pub struct FileWatcher {
+    // Path of the watched file.
+    pub path: String,
+    // Modification time seen at the last poll, or None if the file could not be read.
+    modified: Option<SystemTime>,
+    // When the most recent unreported change was seen.
+    changed_at: Option<Instant>,
+    // When the modification time was last checked.
+    last_poll: Instant,
+}
+
+impl FileWatcher {
+    // Starts watching a file, treating its current contents as already loaded.
+    pub fn new(path: &str) -> FileWatcher {
+        FileWatcher {
+            path: String::from(path),
+            modified: modified_time(path),
+            changed_at: None,
+            last_poll: Instant::now(),
+        }
+    }
+
+    // Returns true once the file has changed and then settled for the debounce duration.
+    pub fn poll(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        // Every new write restarts the debounce.
+        let modified = modified_time(&self.path);
+        if modified != self.modified {
+            self.modified = modified;
+            self.changed_at = Some(self.last_poll);
+            return false;
+        }
+
+        match self.changed_at {
+            Some(changed_at) if changed_at.elapsed() >= DEBOUNCE_DURATION => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Reports the file as changed again after the debounce duration, for when it could not be loaded partway through a save.
+    pub fn retry(&mut self) {
+        self.changed_at = Some(Instant::now());
+    }
+}
+
+// Returns the modification time of a file, or None if it is missing or unreadable.
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}